@@ -0,0 +1,136 @@
+//! Reads the `mod dNN;` solver modules declared in `lib.rs` and generates the registry entries
+//! for them, so adding a day only requires declaring its module, which `aoc2022 new` does.
+//!
+//! A day with a `dNN` feature in `Cargo.toml` is only compiled when that feature is enabled.
+//! Days without one, e.g. freshly scaffolded ones, are always compiled. If any day is left
+//! out, `cfg(some_days_disabled)` is set.
+//!
+//! With the `embed-inputs` feature, the real and sample inputs of the compiled days found in
+//! `$AOC_INPUT_DIR` or `res` are embedded as well.
+
+use std::{
+    env,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+fn main() {
+    let root = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).to_owned();
+    let src_dir = root.join("src");
+    let out_path = Path::new(&env::var("OUT_DIR").unwrap()).join("days.rs");
+
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    println!("cargo:rerun-if-env-changed=AOC_INPUT_DIR");
+    println!("cargo:rustc-check-cfg=cfg(some_days_disabled)");
+
+    let manifest = fs::read_to_string(root.join("Cargo.toml")).expect("Reading Cargo.toml");
+    let features = declared_features(&manifest);
+
+    let lib = fs::read_to_string(src_dir.join("lib.rs")).expect("Reading src/lib.rs");
+    let mut days = declared_days(&lib);
+
+    days.sort();
+
+    let (days, disabled): (Vec<_>, Vec<_>) = days.into_iter().partition(|module| {
+        !features.contains(module)
+            || env::var_os(format!("CARGO_FEATURE_{}", module.to_uppercase())).is_some()
+    });
+
+    // Lets the helpers only the solvers use allow going unused when some are compiled out.
+    if !disabled.is_empty() {
+        println!("cargo:rustc-cfg=some_days_disabled");
+    }
+
+    let mut generated = String::new();
+
+    // Without any day the registry goes unused.
+    let registry = if days.is_empty() {
+        "_registry"
+    } else {
+        "registry"
+    };
+    writeln!(
+        generated,
+        "pub(crate) fn register_all({registry}: &mut crate::registry::Registry) {{"
+    )
+    .unwrap();
+    for module in &days {
+        writeln!(generated, "    {module}::register(registry);").unwrap();
+    }
+    writeln!(generated, "}}").unwrap();
+
+    writeln!(
+        generated,
+        "\n/// Days with a solver module whose feature is disabled.\n\
+         pub(crate) const DISABLED_DAYS: &[u8] = &{:?};",
+        disabled
+            .iter()
+            .map(|module| module[1..].parse::<u8>().unwrap())
+            .collect::<Vec<_>>()
+    )
+    .unwrap();
+
+    let input_dir = root.join(env::var_os("AOC_INPUT_DIR").unwrap_or_else(|| "res".into()));
+    let embedded = if env::var_os("CARGO_FEATURE_EMBED_INPUTS").is_some() {
+        println!("cargo:rerun-if-changed={}", input_dir.display());
+        embedded_inputs(&input_dir, &days)
+    } else {
+        Vec::new()
+    };
+
+    writeln!(
+        generated,
+        "\n/// Input files embedded at build time, by file name.\n\
+         pub(crate) const EMBEDDED_INPUTS: &[(&str, &[u8])] = &["
+    )
+    .unwrap();
+    for (name, path) in &embedded {
+        writeln!(
+            generated,
+            "    ({name:?}, include_bytes!({:?})),",
+            path.display().to_string()
+        )
+        .unwrap();
+    }
+    writeln!(generated, "];").unwrap();
+
+    fs::write(out_path, generated).expect("Writing generated registry");
+}
+
+/// The `dNN` solver modules declared in `lib`, the source of `lib.rs`.
+fn declared_days(lib: &str) -> Vec<String> {
+    lib.lines()
+        .filter_map(|line| {
+            let module = line.trim().strip_prefix("pub mod ")?.strip_suffix(';')?;
+            let day = module.strip_prefix('d')?;
+            (day.len() == 2 && day.parse::<u8>().is_ok()).then(|| module.to_owned())
+        })
+        .collect()
+}
+
+/// The names of the features declared in the `[features]` table of `manifest`.
+fn declared_features(manifest: &str) -> Vec<String> {
+    manifest
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| *line != "[features]")
+        .skip(1)
+        .take_while(|line| !line.starts_with('['))
+        .filter_map(|line| Some(line.split_once('=')?.0.trim().to_owned()))
+        .collect()
+}
+
+/// The name and path of every input file in `input_dir` for one of the `days` modules.
+fn embedded_inputs(input_dir: &Path, days: &[String]) -> Vec<(String, PathBuf)> {
+    days.iter()
+        .map(|module| module[1..].parse::<u8>().unwrap())
+        .flat_map(|day| [format!("day_{day}.txt"), format!("day_{day}_sample.txt")])
+        .map(|name| {
+            let path = input_dir.join(&name);
+            (name, path)
+        })
+        .filter(|(_, path)| path.is_file())
+        .collect()
+}
@@ -0,0 +1,157 @@
+use std::{convert::Infallible, fmt, str::FromStr};
+
+/// The answer to one part of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Answer {
+    Integer(i128),
+    /// A single line of text, e.g. the crate labels from day 5.
+    Text(String),
+    /// Several lines of text, e.g. the letters drawn on the CRT in day 10.
+    Multiline(String),
+}
+
+impl Answer {
+    #[must_use]
+    pub const fn is_multiline(&self) -> bool {
+        matches!(self, Self::Multiline(_))
+    }
+
+    /// The name of the variant. Together with the `Display` output it is a lossless encoding
+    /// of the answer, which [`Answer::from_kind`] reads back.
+    #[must_use]
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::Integer(_) => "integer",
+            Self::Text(_) => "text",
+            Self::Multiline(_) => "multiline",
+        }
+    }
+
+    /// The answer of variant `kind`, as named by [`Answer::kind`], that displays as `text`.
+    ///
+    /// Returns `None` for an unknown kind, or an integer that does not parse.
+    #[must_use]
+    pub fn from_kind(kind: &str, text: &str) -> Option<Self> {
+        match kind {
+            "integer" => text.parse().ok().map(Self::Integer),
+            "text" => Some(Self::Text(text.to_owned())),
+            "multiline" => Some(Self::Multiline(text.to_owned())),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(value) => write!(f, "{value}"),
+            Self::Text(text) | Self::Multiline(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+/// Parses the output of [`Answer`]'s `Display` implementation back into an answer.
+///
+/// The variant is not part of that output, so this is lossy for text that reads as a
+/// number: `Answer::Text("123")` parses back as `Answer::Integer(123)`, and for multiline
+/// answers without a line break. Use [`Answer::kind`] and [`Answer::from_kind`] to keep the
+/// variant.
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(if let Ok(value) = s.parse() {
+            Self::Integer(value)
+        } else if s.contains('\n') {
+            Self::Multiline(s.to_owned())
+        } else {
+            Self::Text(s.to_owned())
+        })
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Answer {
+                fn from(value: $ty) -> Self {
+                    Self::Integer(i128::from(value))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Self::Integer(value as i128)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        if text.contains('\n') {
+            Self::Multiline(text)
+        } else {
+            Self::Text(text)
+        }
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Self::from(text.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Answer;
+
+    #[test]
+    fn display_round_trips() {
+        for answer in [
+            Answer::Integer(-42),
+            Answer::from(2_713_310_158_u64),
+            Answer::from("CMZ"),
+            Answer::from("#..#\n.##.\n"),
+        ] {
+            assert_eq!(Ok(answer.clone()), answer.to_string().parse());
+        }
+    }
+
+    #[test]
+    fn numeric_text_parses_back_as_an_integer() {
+        let answer = Answer::Text("0123".to_string());
+
+        assert_eq!(Ok(Answer::Integer(123)), answer.to_string().parse());
+        assert_ne!(Ok(answer), "0123".parse());
+    }
+
+    #[test]
+    fn kind_and_display_round_trip_every_variant() {
+        for answer in [
+            Answer::Integer(-42),
+            Answer::Text("0123".to_string()),
+            Answer::Text("CMZ".to_string()),
+            Answer::Multiline("#..#".to_string()),
+            Answer::from("#..#\n.##.\n"),
+        ] {
+            assert_eq!(
+                Some(answer.clone()),
+                Answer::from_kind(answer.kind(), &answer.to_string())
+            );
+        }
+        assert_eq!(None, Answer::from_kind("integer", "CMZ"));
+        assert_eq!(None, Answer::from_kind("float", "1.5"));
+    }
+
+    #[test]
+    fn text_with_newlines_is_multiline() {
+        assert!(Answer::from("##..\n..##").is_multiline());
+        assert!(!Answer::from("MCD").is_multiline());
+    }
+}
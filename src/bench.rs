@@ -0,0 +1,252 @@
+use std::{
+    fmt,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use crate::{runner::isolate, AocError, Day, InputSource, Part, Registry};
+
+/// How often [`bench_day`] runs each phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchOptions {
+    /// Untimed runs before measuring, to warm up caches and the allocator.
+    pub warmup: usize,
+    /// Timed runs. At least one sample is always taken.
+    pub samples: usize,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            warmup: 3,
+            samples: 30,
+        }
+    }
+}
+
+/// A separately timed step of solving a day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Parse,
+    Part(Part),
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse => f.pad("parse"),
+            Self::Part(part) => f.pad(&format!("part {part}")),
+        }
+    }
+}
+
+/// Summary statistics over a set of timings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub samples: usize,
+    pub mean: Duration,
+    pub median: Duration,
+    /// Sample standard deviation, zero for a single sample.
+    pub std_dev: Duration,
+    pub min: Duration,
+    pub max: Duration,
+    /// Samples outside the Tukey fences, 1.5 interquartile ranges beyond the quartiles.
+    pub outliers: usize,
+}
+
+impl Stats {
+    /// Summarises `samples`, or returns `None` if there are none.
+    #[must_use]
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+
+        let (&min, &max) = (sorted.first()?, sorted.last()?);
+        let len = sorted.len();
+
+        let secs = sorted.iter().map(Duration::as_secs_f64).collect::<Vec<_>>();
+        let mean = secs.iter().sum::<f64>() / len as f64;
+        let variance = if len > 1 {
+            secs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (len - 1) as f64
+        } else {
+            0.0
+        };
+
+        let median = if len.is_multiple_of(2) {
+            (sorted[len / 2 - 1] + sorted[len / 2]) / 2
+        } else {
+            sorted[len / 2]
+        };
+
+        let (q1, q3) = (secs[len / 4], secs[len * 3 / 4]);
+        let fence = 1.5 * (q3 - q1);
+        let outliers = secs
+            .iter()
+            .filter(|&&x| x < q1 - fence || x > q3 + fence)
+            .count();
+
+        Some(Self {
+            samples: len,
+            mean: Duration::from_secs_f64(mean),
+            median,
+            std_dev: Duration::from_secs_f64(variance.sqrt()),
+            min,
+            max,
+            outliers,
+        })
+    }
+}
+
+/// Timings for one phase of a day.
+#[derive(Debug)]
+pub struct PhaseBench {
+    pub phase: Phase,
+    pub stats: Stats,
+}
+
+/// Timings for every benchmarked phase of a day.
+#[derive(Debug)]
+pub struct DayBench {
+    pub day: Day,
+    /// One entry for parsing and one per part, or the first error hit while benchmarking.
+    pub phases: Result<Vec<PhaseBench>, AocError>,
+}
+
+/// Repeatedly parses the input for `day` and solves each of `parts`, timing every phase.
+///
+/// `source` is read once per parse, so it must be readable more than once; standard input
+/// is not. A panic becomes [`AocError::Panicked`], see [`quiet_panics`](crate::quiet_panics).
+#[must_use]
+pub fn bench_day(
+    registry: &Registry,
+    day: Day,
+    parts: &[Part],
+    source: &InputSource,
+    options: BenchOptions,
+) -> DayBench {
+    DayBench {
+        day,
+        phases: isolate(day, None, || {
+            bench_phases(registry, day, parts, source, options)
+        }),
+    }
+}
+
+/// Benchmarks each of `days` in order.
+#[must_use]
+pub fn bench(
+    registry: &Registry,
+    days: &[Day],
+    parts: &[Part],
+    source: &InputSource,
+    options: BenchOptions,
+) -> Vec<DayBench> {
+    days.iter()
+        .map(|&day| bench_day(registry, day, parts, source, options))
+        .collect()
+}
+
+fn bench_phases(
+    registry: &Registry,
+    day: Day,
+    parts: &[Part],
+    source: &InputSource,
+    options: BenchOptions,
+) -> Result<Vec<PhaseBench>, AocError> {
+    let solver = registry.get(day).ok_or_else(|| AocError::unsolved(day))?;
+
+    let input = solver.parse(source)?;
+    let mut phases = vec![PhaseBench {
+        phase: Phase::Parse,
+        stats: measure(options, || solver.parse(source).map(|_| ()))?,
+    }];
+
+    for &part in parts {
+        phases.push(PhaseBench {
+            phase: Phase::Part(part),
+            stats: measure(options, || solver.solve_parsed(part, &input))?,
+        });
+    }
+
+    Ok(phases)
+}
+
+fn measure<T>(
+    options: BenchOptions,
+    mut f: impl FnMut() -> Result<T, AocError>,
+) -> Result<Stats, AocError> {
+    for _ in 0..options.warmup {
+        black_box(f()?);
+    }
+
+    let mut samples = Vec::with_capacity(options.samples.max(1));
+    for _ in 0..options.samples.max(1) {
+        let start = Instant::now();
+        black_box(f()?);
+        samples.push(start.elapsed());
+    }
+
+    Ok(Stats::from_samples(&samples).expect("at least one sample is taken"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    #[cfg(feature = "d06")]
+    use crate::{Day, InputSource, Part, Registry};
+
+    use super::Stats;
+    #[cfg(feature = "d06")]
+    use super::{bench_day, BenchOptions, Phase};
+
+    #[test]
+    fn stats_of_known_samples() {
+        let samples = [10, 12, 11, 13, 12, 11, 50, 12]
+            .map(Duration::from_millis)
+            .to_vec();
+
+        let stats = Stats::from_samples(&samples).unwrap();
+
+        assert_eq!(8, stats.samples);
+        assert!(stats.mean.abs_diff(Duration::from_micros(16_375)) < Duration::from_micros(1));
+        assert_eq!(Duration::from_millis(12), stats.median);
+        assert_eq!(Duration::from_millis(10), stats.min);
+        assert_eq!(Duration::from_millis(50), stats.max);
+        assert_eq!(1, stats.outliers);
+        assert!(stats.std_dev > Duration::from_millis(13));
+        assert!(stats.std_dev < Duration::from_millis(14));
+
+        assert!(Stats::from_samples(&[]).is_none());
+    }
+
+    #[cfg(feature = "d06")]
+    #[test]
+    fn bench_times_parse_and_each_part() {
+        let options = BenchOptions {
+            warmup: 1,
+            samples: 5,
+        };
+
+        let day_bench = bench_day(
+            Registry::global(),
+            Day::new(6),
+            &[Part::Two],
+            &InputSource::Sample,
+            options,
+        );
+
+        let phases = day_bench.phases.unwrap();
+        let phases = phases.iter().map(|p| p.phase).collect::<Vec<_>>();
+        assert_eq!(vec![Phase::Parse, Phase::Part(Part::Two)], phases);
+
+        let unsolved = bench_day(
+            Registry::global(),
+            Day::new(25),
+            &Part::ALL,
+            &InputSource::Sample,
+            options,
+        );
+        assert!(unsolved.phases.is_err());
+    }
+}
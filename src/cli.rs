@@ -0,0 +1,1010 @@
+use std::{fmt, path::PathBuf, time::Duration};
+
+use aoc2022::{
+    BenchOptions, Config, Day, Format, InputSource, Part, DEFAULT_CONFIG_FILE,
+    DEFAULT_HISTORY_FILE, DEFAULT_INTERVAL, DEFAULT_POLL_INTERVAL,
+};
+
+pub const USAGE: &str = "\
+Usage: aoc2022 [OPTIONS] [DAYS]...
+       aoc2022 bench [OPTIONS] [BENCH OPTIONS] [DAYS]...
+       aoc2022 verify [OPTIONS] [VERIFY OPTIONS] [DAYS]...
+       aoc2022 fetch [OPTIONS] [SERVER OPTIONS] [DAYS]...
+       aoc2022 submit [OPTIONS] [SERVER OPTIONS] [SUBMIT OPTIONS] DAY --part PART [ANSWER]
+       aoc2022 scrape [OPTIONS] [SERVER OPTIONS] [SCRAPE OPTIONS] [DAYS]...
+       aoc2022 new [OPTIONS] [NEW OPTIONS] DAY
+       aoc2022 watch [OPTIONS] [WATCH OPTIONS] [DAYS]...
+       aoc2022 encrypt [OPTIONS] [ENCRYPT OPTIONS] [DAYS]...
+
+Arguments:
+  [DAYS]...             Days to run, e.g. `7`, `1-5` or `3,8,11` (default: all solved days)
+  [ANSWER]              Answer to submit (default: the answer solved from the real input)
+
+The default days, input directory, format, jobs, parameters and session file can be set in
+aoc.toml; options given here take precedence.
+
+Options:
+  -p, --part <PART>     Only run part 1 or part 2
+  -s, --sample          Use res/day_N_sample.txt instead of res/day_N.txt
+  -i, --input <PATH>    Read the puzzle input from PATH, or stdin for `-` (requires a single day)
+  -d, --input-dir <DIR> Read day_N.txt files from DIR (default: $AOC_INPUT_DIR or res)
+  -f, --format <FORMAT> Print results as text, json, csv or markdown (default: text)
+  -j, --jobs <N>        Run up to N days at once (default: 1, run and watch only)
+  -P, --param <DAY.NAME=VALUE>
+                        Override a solver parameter, e.g. `11.long_game=5000` (repeatable)
+  -C, --config <FILE>   Read defaults from FILE (default: aoc.toml)
+  -l, --list            List the days that have a solver and their parameters
+  -h, --help            Print this message
+
+Bench options:
+  -w, --warmup <N>      Untimed runs before measuring (default: 3)
+  -n, --samples <N>     Timed runs of each phase (default: 30)
+      --save            Append the results to the history file, keyed by git commit and time
+  -c, --compare         Compare against the latest run saved in the history file
+      --baseline <REV>  Compare against the latest saved run of commit REV
+  -t, --threshold <PCT> Flag phases whose median got more than PCT% slower (default: 10)
+      --history <FILE>  Where results are saved (default: bench_history.tsv)
+
+Verify options:
+  -m, --manifest <FILE> Expected answers (default: answers.tsv, or answers_sample.tsv with
+                        --sample, in the input directory)
+  -u, --update          Record the current answers in the manifest instead of failing
+
+Server options:
+      --base-url <URL>  Server to talk to (default: $AOC_BASE_URL or https://adventofcode.com).
+                        HTTPS requests run the `curl` executable, which must be installed.
+      --session-file <FILE>
+                        Read the session token from FILE unless $AOC_SESSION is set
+                        (default: .aoc_session)
+      --interval <SECS> Wait at least SECS seconds between requests, also across runs
+                        through .aoc_last_request next to the session file (default: 5)
+
+Submit options:
+      --guesses <FILE>  Where guesses are recorded and checked (default: guesses.tsv in the
+                        input directory)
+
+Scrape options:
+      --page <FILE>     Read a saved puzzle page instead of downloading it (requires a single
+                        day)
+      --src <DIR>       Where the solver modules whose sample tests get the answers live
+                        (default: src)
+
+New options:
+      --title <TITLE>   Puzzle title for the module docs
+      --src <DIR>       Where the solver modules live (default: src)
+
+Watch options:
+      --poll <MILLIS>   How often to look for changed inputs (default: 500)
+
+Encrypt options:
+      --new-key         Print a new random key instead of encrypting
+
+`encrypt` writes day_N.txt.enc next to each day_N.txt with the key in $AOC_INPUT_KEY, which
+is also what the encrypted inputs are read with. Inputs that are only kept encrypted are
+moved over from the key in $AOC_OLD_INPUT_KEY. Both need a build with the `encrypted-inputs`
+feature.";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run(Selection),
+    /// Time each selected day repeatedly and report statistics.
+    Bench(Selection, BenchArgs),
+    /// Check answers against the expected-answers manifest.
+    Verify(Selection, VerifyArgs),
+    /// Download missing real inputs into the input directory.
+    Fetch(Selection, ServerArgs),
+    /// Post an answer for one day and part.
+    Submit(Selection, SubmitArgs),
+    /// Save the sample input and answers from puzzle pages.
+    Scrape(Selection, ScrapeArgs),
+    /// Generate the solver module and sample file of a new day.
+    New(Selection, NewArgs),
+    /// Run the selected days, then again whenever their input changes.
+    Watch(Selection, WatchArgs),
+    /// Encrypt the real inputs, or move them to a new key.
+    Encrypt(Selection, EncryptArgs),
+    List,
+    Help,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Selection {
+    /// Empty means every solved day.
+    pub days: Vec<Day>,
+    pub parts: Vec<Part>,
+    pub input: InputSource,
+    pub input_dir: Option<PathBuf>,
+    /// Whether `--input-dir` was given, which reads inputs from it rather than embedded copies.
+    pub explicit_input_dir: bool,
+    /// `None` prints human-readable text.
+    pub format: Option<Format>,
+    /// How many days may run at once.
+    pub jobs: usize,
+    /// Solver parameter overrides as `(day, name, value)`, in the order given.
+    pub params: Vec<(Day, String, String)>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct BenchArgs {
+    pub options: BenchOptions,
+    pub history: PathBuf,
+    pub save: bool,
+    pub compare: bool,
+    /// Commit to compare against, or the latest saved run if `None`.
+    pub baseline: Option<String>,
+    /// Allowed slowdown in percent before a phase counts as a regression.
+    pub threshold: u32,
+}
+
+impl Default for BenchArgs {
+    fn default() -> Self {
+        Self {
+            options: BenchOptions::default(),
+            history: PathBuf::from(DEFAULT_HISTORY_FILE),
+            save: false,
+            compare: false,
+            baseline: None,
+            threshold: 10,
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct VerifyArgs {
+    /// `None` uses the manifest next to the inputs.
+    pub manifest: Option<PathBuf>,
+    pub update: bool,
+}
+
+/// How to reach the puzzle server.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ServerArgs {
+    /// `None` uses `$AOC_BASE_URL` or the Advent of Code website.
+    pub base_url: Option<String>,
+    /// `None` uses the default session file.
+    pub session_file: Option<PathBuf>,
+    pub interval: Duration,
+}
+
+impl Default for ServerArgs {
+    fn default() -> Self {
+        Self {
+            base_url: None,
+            session_file: None,
+            interval: DEFAULT_INTERVAL,
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SubmitArgs {
+    pub server: ServerArgs,
+    /// `None` submits the answer solved from the selected input.
+    pub answer: Option<String>,
+    /// `None` uses the guess file in the input directory.
+    pub guesses: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ScrapeArgs {
+    pub server: ServerArgs,
+    /// `None` downloads the puzzle page.
+    pub page: Option<PathBuf>,
+    /// Where the solver modules whose sample tests are filled in live.
+    pub src: PathBuf,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct NewArgs {
+    pub title: Option<String>,
+    pub src: PathBuf,
+}
+
+impl Default for NewArgs {
+    fn default() -> Self {
+        Self {
+            title: None,
+            src: PathBuf::from("src"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct WatchArgs {
+    pub poll: Duration,
+}
+
+impl Default for WatchArgs {
+    fn default() -> Self {
+        Self {
+            poll: DEFAULT_POLL_INTERVAL,
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct EncryptArgs {
+    /// Print a fresh key instead of encrypting anything.
+    pub new_key: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct UsageError(String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Run,
+    Bench,
+    Verify,
+    Fetch,
+    Submit,
+    Scrape,
+    New,
+    Watch,
+    Encrypt,
+}
+
+/// The configuration file named by `--config`, or the default one.
+pub fn config_path(args: &[String]) -> PathBuf {
+    args.iter()
+        .position(|arg| arg == "-C" || arg == "--config")
+        .and_then(|idx| args.get(idx + 1))
+        .map_or_else(|| PathBuf::from(DEFAULT_CONFIG_FILE), PathBuf::from)
+}
+
+/// Parses the command line, starting from the defaults in `config`.
+pub fn parse_args(
+    args: impl IntoIterator<Item = String>,
+    config: &Config,
+) -> Result<Command, UsageError> {
+    let mut args = args.into_iter().peekable();
+
+    let mode = match args.peek().map(String::as_str) {
+        Some("bench") => Mode::Bench,
+        Some("verify") => Mode::Verify,
+        Some("fetch") => Mode::Fetch,
+        Some("submit") => Mode::Submit,
+        Some("scrape") => Mode::Scrape,
+        Some("new") => Mode::New,
+        Some("watch") => Mode::Watch,
+        Some("encrypt") => Mode::Encrypt,
+        _ => Mode::Run,
+    };
+    if mode != Mode::Run {
+        args.next();
+    }
+    let bench = mode == Mode::Bench;
+    let mut bench_args = BenchArgs::default();
+    let mut verify_args = VerifyArgs::default();
+    let mut server_args = ServerArgs {
+        session_file: config.session_file.clone(),
+        ..ServerArgs::default()
+    };
+    let mut submit_args = SubmitArgs::default();
+    let mut page = None;
+    let mut new_args = NewArgs::default();
+    let mut watch_args = WatchArgs::default();
+    let mut encrypt_args = EncryptArgs::default();
+    let server = matches!(mode, Mode::Fetch | Mode::Submit | Mode::Scrape);
+
+    let mut days = Vec::new();
+    let mut parts = Part::ALL.to_vec();
+    let mut sample = false;
+    let mut path = None;
+    let mut input_dir = config.input_dir.clone();
+    let mut explicit_input_dir = false;
+    let mut format = config.format;
+    let mut jobs = config.jobs.unwrap_or(1);
+    let mut params = config.params.clone();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-l" | "--list" => return Ok(Command::List),
+            // Read by `config_path` before parsing.
+            "-C" | "--config" => {
+                args.next().ok_or_else(|| missing_value(&arg))?;
+            }
+            "-s" | "--sample" => sample = true,
+            "-p" | "--part" => {
+                let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                parts = vec![parse_part(&value)?];
+            }
+            "-i" | "--input" => {
+                let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                path = Some(PathBuf::from(value));
+            }
+            "-d" | "--input-dir" => {
+                let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                input_dir = Some(PathBuf::from(value));
+                explicit_input_dir = true;
+            }
+            "-f" | "--format" if mode == Mode::Run => {
+                let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                format = parse_format(&value)?;
+            }
+            "-j" | "--jobs" if matches!(mode, Mode::Run | Mode::Watch) => {
+                let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                jobs = parse_count(&arg, &value)?;
+                if jobs == 0 {
+                    return Err(UsageError("`--jobs` must be at least 1".to_string()));
+                }
+            }
+            "-P" | "--param" => {
+                let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                params.push(parse_param(&value)?);
+            }
+            "-w" | "--warmup" if bench => {
+                let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                bench_args.options.warmup = parse_count(&arg, &value)?;
+            }
+            "-n" | "--samples" if bench => {
+                let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                bench_args.options.samples = parse_count(&arg, &value)?;
+                if bench_args.options.samples == 0 {
+                    return Err(UsageError("`--samples` must be at least 1".to_string()));
+                }
+            }
+            "--save" if bench => bench_args.save = true,
+            "-c" | "--compare" if bench => bench_args.compare = true,
+            "--baseline" if bench => {
+                let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                bench_args.compare = true;
+                bench_args.baseline = Some(value);
+            }
+            "-t" | "--threshold" if bench => {
+                let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                bench_args.threshold = parse_count(&arg, &value)?.try_into().map_err(|_| {
+                    UsageError(format!("`{arg}` is too large. Got `{value}` instead."))
+                })?;
+            }
+            "--history" if bench => {
+                let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                bench_args.history = PathBuf::from(value);
+            }
+            "-m" | "--manifest" if mode == Mode::Verify => {
+                let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                verify_args.manifest = Some(PathBuf::from(value));
+            }
+            "-u" | "--update" if mode == Mode::Verify => verify_args.update = true,
+            "--base-url" if server => {
+                let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                server_args.base_url = Some(value);
+            }
+            "--session-file" if server => {
+                let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                server_args.session_file = Some(PathBuf::from(value));
+            }
+            "--interval" if server => {
+                let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                server_args.interval = Duration::from_secs(parse_count(&arg, &value)? as u64);
+            }
+            "--page" if mode == Mode::Scrape => {
+                let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                page = Some(PathBuf::from(value));
+            }
+            "--title" if mode == Mode::New => {
+                let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                new_args.title = Some(value);
+            }
+            "--src" if matches!(mode, Mode::New | Mode::Scrape) => {
+                let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                new_args.src = PathBuf::from(value);
+            }
+            "--poll" if mode == Mode::Watch => {
+                let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                let millis = parse_count(&arg, &value)?;
+                if millis == 0 {
+                    return Err(UsageError("`--poll` must be at least 1".to_string()));
+                }
+                watch_args.poll = Duration::from_millis(millis as u64);
+            }
+            "--new-key" if mode == Mode::Encrypt => encrypt_args.new_key = true,
+            "--guesses" if mode == Mode::Submit => {
+                let value = args.next().ok_or_else(|| missing_value(&arg))?;
+                submit_args.guesses = Some(PathBuf::from(value));
+            }
+            // The day comes first; anything after it, including negative numbers, is the answer.
+            _ if mode == Mode::Submit
+                && !days.is_empty()
+                && (!arg.starts_with('-') || arg.parse::<i128>().is_ok()) =>
+            {
+                if submit_args.answer.replace(arg).is_some() {
+                    return Err(UsageError("`submit` takes a single answer".to_string()));
+                }
+            }
+            _ if arg.starts_with('-') => {
+                return Err(UsageError(format!("unknown option `{arg}`")));
+            }
+            _ => days.extend(parse_days(&arg)?),
+        }
+    }
+
+    days.sort_unstable();
+    days.dedup();
+
+    if mode == Mode::Fetch && (sample || path.is_some()) {
+        return Err(UsageError(
+            "`fetch` only downloads real inputs into the input directory".to_string(),
+        ));
+    }
+    if mode == Mode::Encrypt && (sample || path.is_some()) {
+        return Err(UsageError(
+            "`encrypt` only encrypts real inputs in the input directory".to_string(),
+        ));
+    }
+    if mode == Mode::Scrape && (sample || path.is_some()) {
+        return Err(UsageError(
+            "`scrape` writes sample inputs into the input directory".to_string(),
+        ));
+    }
+    if mode == Mode::New && (days.len() != 1 || sample || path.is_some()) {
+        return Err(UsageError(
+            "`new` requires exactly one day and no input".to_string(),
+        ));
+    }
+    if page.is_some() && days.len() != 1 {
+        return Err(UsageError(
+            "`--page` requires exactly one day to be selected".to_string(),
+        ));
+    }
+    if mode == Mode::Submit {
+        if days.len() != 1 || parts.len() != 1 {
+            return Err(UsageError(
+                "`submit` requires exactly one day and `--part`".to_string(),
+            ));
+        }
+        if sample {
+            return Err(UsageError("sample answers cannot be submitted".to_string()));
+        }
+    }
+
+    let input = match (sample, path) {
+        (true, Some(_)) => {
+            return Err(UsageError(
+                "`--sample` and `--input` cannot be used together".to_string(),
+            ))
+        }
+        (false, Some(path)) => {
+            if days.len() != 1 {
+                return Err(UsageError(
+                    "`--input` requires exactly one day to be selected".to_string(),
+                ));
+            }
+            if path.as_os_str() == "-" {
+                if bench {
+                    return Err(UsageError(
+                        "standard input can only be read once and cannot be benchmarked"
+                            .to_string(),
+                    ));
+                }
+                if mode == Mode::Watch {
+                    return Err(UsageError("standard input cannot be watched".to_string()));
+                }
+                InputSource::Stdin
+            } else {
+                InputSource::Path(path)
+            }
+        }
+        (true, None) => InputSource::Sample,
+        (false, None) => InputSource::Real,
+    };
+
+    if days.is_empty() {
+        days.clone_from(&config.days);
+    }
+
+    let selection = Selection {
+        days,
+        parts,
+        input,
+        input_dir,
+        explicit_input_dir,
+        format,
+        jobs,
+        params,
+    };
+
+    Ok(match mode {
+        Mode::Run => Command::Run(selection),
+        Mode::Bench => Command::Bench(selection, bench_args),
+        Mode::Verify => Command::Verify(selection, verify_args),
+        Mode::Fetch => Command::Fetch(selection, server_args),
+        Mode::Submit => Command::Submit(
+            selection,
+            SubmitArgs {
+                server: server_args,
+                ..submit_args
+            },
+        ),
+        Mode::New => Command::New(selection, new_args),
+        Mode::Watch => Command::Watch(selection, watch_args),
+        Mode::Encrypt => Command::Encrypt(selection, encrypt_args),
+        Mode::Scrape => Command::Scrape(
+            selection,
+            ScrapeArgs {
+                server: server_args,
+                page,
+                src: new_args.src,
+            },
+        ),
+    })
+}
+
+fn missing_value(option: &str) -> UsageError {
+    UsageError(format!("`{option}` requires a value"))
+}
+
+fn parse_count(option: &str, value: &str) -> Result<usize, UsageError> {
+    value.parse().map_err(|_| {
+        UsageError(format!(
+            "`{option}` expects a number. Got `{value}` instead."
+        ))
+    })
+}
+
+fn parse_format(value: &str) -> Result<Option<Format>, UsageError> {
+    if value == "text" {
+        return Ok(None);
+    }
+
+    value.parse().map(Some).map_err(|_| {
+        UsageError(format!(
+            "format must be text, json, csv or markdown. Got `{value}` instead."
+        ))
+    })
+}
+
+fn parse_part(value: &str) -> Result<Part, UsageError> {
+    match value {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(UsageError(format!(
+            "part must be 1 or 2. Got `{value}` instead."
+        ))),
+    }
+}
+
+fn parse_day(value: &str) -> Result<Day, UsageError> {
+    value
+        .trim()
+        .parse()
+        .ok()
+        .and_then(Day::try_new)
+        .ok_or_else(|| {
+            UsageError(format!(
+                "day must be between 1 - 25. Got `{value}` instead."
+            ))
+        })
+}
+
+fn parse_param(value: &str) -> Result<(Day, String, String), UsageError> {
+    let (day, assignment) = value
+        .split_once('.')
+        .ok_or_else(|| UsageError(format!("expected DAY.NAME=VALUE. Got `{value}` instead.")))?;
+    let (name, param) = assignment
+        .split_once('=')
+        .filter(|(name, _)| !name.is_empty())
+        .ok_or_else(|| UsageError(format!("expected DAY.NAME=VALUE. Got `{value}` instead.")))?;
+
+    Ok((parse_day(day)?, name.to_string(), param.to_string()))
+}
+
+fn parse_days(value: &str) -> Result<Vec<Day>, UsageError> {
+    let mut days = Vec::new();
+
+    for item in value.split(',') {
+        if let Some((start, end)) = item.split_once('-') {
+            let (start, end) = (parse_day(start)?, parse_day(end)?);
+            if start > end {
+                return Err(UsageError(format!("`{item}` is an empty range of days")));
+            }
+            days.extend((start.get()..=end.get()).map(Day::new));
+        } else {
+            days.push(parse_day(item)?);
+        }
+    }
+
+    Ok(days)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use aoc2022::{BenchOptions, Config, Day, Format, InputSource, Part};
+
+    use super::{
+        config_path, parse_args, BenchArgs, Command, EncryptArgs, NewArgs, ScrapeArgs, Selection,
+        ServerArgs, SubmitArgs, VerifyArgs, WatchArgs,
+    };
+
+    fn parse(args: &[&str]) -> Result<Command, super::UsageError> {
+        parse_args(args.iter().map(ToString::to_string), &Config::default())
+    }
+
+    #[test]
+    fn parse_no_arguments() {
+        let expected = Command::Run(Selection {
+            days: Vec::new(),
+            parts: vec![Part::One, Part::Two],
+            input: InputSource::Real,
+            input_dir: None,
+            explicit_input_dir: false,
+            format: None,
+            jobs: 1,
+            params: Vec::new(),
+        });
+
+        assert_eq!(Ok(expected), parse(&[]));
+    }
+
+    #[test]
+    fn parse_days_ranges_and_parts() {
+        let expected = Command::Run(Selection {
+            days: [1, 2, 3, 7, 9].into_iter().map(Day::new).collect(),
+            parts: vec![Part::Two],
+            input: InputSource::Sample,
+            input_dir: Some("inputs".into()),
+            explicit_input_dir: true,
+            format: Some(Format::Csv),
+            jobs: 4,
+            params: Vec::new(),
+        });
+
+        assert_eq!(
+            Ok(expected),
+            parse(&["9", "1-3,7", "--part", "2", "-s", "-d", "inputs", "-f", "csv", "-j", "4"])
+        );
+    }
+
+    #[test]
+    fn parse_explicit_input() {
+        let expected = Command::Run(Selection {
+            days: vec![Day::new(6)],
+            parts: vec![Part::One, Part::Two],
+            input: InputSource::Path("message.txt".into()),
+            input_dir: None,
+            explicit_input_dir: false,
+            format: None,
+            jobs: 1,
+            params: Vec::new(),
+        });
+
+        assert_eq!(Ok(expected), parse(&["6", "-i", "message.txt"]));
+    }
+
+    #[test]
+    fn parse_stdin_input() {
+        let Ok(Command::Run(selection)) = parse(&["6", "--input", "-"]) else {
+            panic!("expected a selection");
+        };
+
+        assert_eq!(InputSource::Stdin, selection.input);
+    }
+
+    #[test]
+    fn parse_bench() {
+        let expected = Command::Bench(
+            Selection {
+                days: vec![Day::new(8)],
+                parts: vec![Part::One],
+                input: InputSource::Real,
+                input_dir: None,
+                explicit_input_dir: false,
+                format: None,
+                jobs: 1,
+                params: Vec::new(),
+            },
+            BenchArgs {
+                options: BenchOptions {
+                    warmup: 0,
+                    samples: 100,
+                },
+                ..BenchArgs::default()
+            },
+        );
+
+        assert_eq!(
+            Ok(expected),
+            parse(&["bench", "8", "-p", "1", "--warmup", "0", "-n", "100"])
+        );
+        assert!(parse(&["bench", "6", "-i", "-"]).is_err());
+        assert!(parse(&["bench", "--samples", "0"]).is_err());
+        assert!(parse(&["8", "--samples", "100"]).is_err());
+    }
+
+    #[test]
+    fn parse_bench_history() {
+        let Ok(Command::Bench(_, args)) = parse(&[
+            "bench",
+            "--save",
+            "--baseline",
+            "abc1234",
+            "-t",
+            "25",
+            "--history",
+            "out.tsv",
+        ]) else {
+            panic!("expected a benchmark");
+        };
+
+        assert!(args.save && args.compare);
+        assert_eq!(Some("abc1234".to_string()), args.baseline);
+        assert_eq!(25, args.threshold);
+        assert_eq!(PathBuf::from("out.tsv"), args.history);
+        assert!(parse(&["--save"]).is_err());
+    }
+
+    #[test]
+    fn parse_verify() {
+        let expected = Command::Verify(
+            Selection {
+                days: vec![Day::new(7)],
+                parts: vec![Part::One, Part::Two],
+                input: InputSource::Sample,
+                input_dir: None,
+                explicit_input_dir: false,
+                format: None,
+                jobs: 1,
+                params: Vec::new(),
+            },
+            VerifyArgs {
+                manifest: Some("expected.tsv".into()),
+                update: true,
+            },
+        );
+
+        assert_eq!(
+            Ok(expected),
+            parse(&["verify", "7", "-s", "-m", "expected.tsv", "--update"])
+        );
+        assert!(parse(&["--update"]).is_err());
+    }
+
+    #[test]
+    fn parse_fetch() {
+        let Ok(Command::Fetch(selection, args)) = parse(&[
+            "fetch",
+            "1-2",
+            "--base-url",
+            "http://127.0.0.1:8080",
+            "--interval",
+            "1",
+        ]) else {
+            panic!("expected a download");
+        };
+
+        assert_eq!(vec![Day::new(1), Day::new(2)], selection.days);
+        assert_eq!(
+            ServerArgs {
+                base_url: Some("http://127.0.0.1:8080".to_string()),
+                session_file: None,
+                interval: std::time::Duration::from_secs(1),
+            },
+            args
+        );
+        assert!(parse(&["fetch", "--sample"]).is_err());
+        assert!(parse(&["--base-url", "http://127.0.0.1:8080"]).is_err());
+    }
+
+    #[test]
+    fn parse_submit() {
+        let Ok(Command::Submit(selection, args)) =
+            parse(&["submit", "11", "-p", "2", "-12", "--guesses", "guesses.tsv"])
+        else {
+            panic!("expected a submission");
+        };
+
+        assert_eq!(
+            (vec![Day::new(11)], vec![Part::Two]),
+            (selection.days, selection.parts)
+        );
+        assert_eq!(
+            SubmitArgs {
+                server: ServerArgs::default(),
+                answer: Some("-12".to_string()),
+                guesses: Some("guesses.tsv".into()),
+            },
+            args
+        );
+        assert!(parse(&["submit", "11", "-p", "2"]).is_ok());
+        assert!(parse(&["submit", "11", "42"]).is_err());
+        assert!(parse(&["submit", "10-11", "-p", "1"]).is_err());
+        assert!(parse(&["submit", "11", "-p", "1", "42", "43"]).is_err());
+        assert!(parse(&["submit", "11", "-p", "1", "--sample"]).is_err());
+        assert!(parse(&["submit", "11", "-p", "1", "--frobnicate"]).is_err());
+    }
+
+    #[test]
+    fn parse_scrape() {
+        let Ok(Command::Scrape(selection, args)) = parse(&[
+            "scrape",
+            "12",
+            "--page",
+            "day12.html",
+            "-d",
+            "inputs",
+            "--src",
+            "s",
+        ]) else {
+            panic!("expected a scrape");
+        };
+
+        assert_eq!(vec![Day::new(12)], selection.days);
+        assert_eq!(
+            ScrapeArgs {
+                server: ServerArgs::default(),
+                page: Some("day12.html".into()),
+                src: "s".into(),
+            },
+            args
+        );
+        assert!(parse(&["scrape", "1-2", "--page", "day12.html"]).is_err());
+        assert!(parse(&["scrape", "12", "--sample"]).is_err());
+        assert!(parse(&["12", "--page", "day12.html"]).is_err());
+    }
+
+    #[test]
+    fn parse_new() {
+        let Ok(Command::New(selection, args)) = parse(&["new", "12", "--title", "Hill Climbing"])
+        else {
+            panic!("expected a new day");
+        };
+
+        assert_eq!(vec![Day::new(12)], selection.days);
+        assert_eq!(
+            NewArgs {
+                title: Some("Hill Climbing".to_string()),
+                ..NewArgs::default()
+            },
+            args
+        );
+        assert!(parse(&["new"]).is_err());
+        assert!(parse(&["new", "12-13"]).is_err());
+        assert!(parse(&["new", "12", "--sample"]).is_err());
+    }
+
+    #[test]
+    fn parse_watch() {
+        let Ok(Command::Watch(selection, args)) =
+            parse(&["watch", "1-3", "--sample", "-j", "2", "--poll", "100"])
+        else {
+            panic!("expected watch mode");
+        };
+
+        assert_eq!(vec![Day::new(1), Day::new(2), Day::new(3)], selection.days);
+        assert_eq!(InputSource::Sample, selection.input);
+        assert_eq!(2, selection.jobs);
+        assert_eq!(
+            WatchArgs {
+                poll: std::time::Duration::from_millis(100)
+            },
+            args
+        );
+        assert!(parse(&["watch", "--poll", "0"]).is_err());
+        assert!(parse(&["watch", "1", "--input", "-"]).is_err());
+        assert!(parse(&["--poll", "100"]).is_err());
+    }
+
+    #[test]
+    fn parse_encrypt() {
+        let Ok(Command::Encrypt(selection, args)) = parse(&["encrypt", "1-2"]) else {
+            panic!("expected encrypt mode");
+        };
+
+        assert_eq!(vec![Day::new(1), Day::new(2)], selection.days);
+        assert_eq!(EncryptArgs::default(), args);
+        assert!(matches!(
+            parse(&["encrypt", "--new-key"]),
+            Ok(Command::Encrypt(_, EncryptArgs { new_key: true }))
+        ));
+        assert!(parse(&["encrypt", "--sample"]).is_err());
+        assert!(parse(&["--new-key"]).is_err());
+    }
+
+    #[test]
+    fn flags_override_the_config() {
+        let config = Config {
+            input_dir: Some(PathBuf::from("inputs")),
+            format: Some(Format::Json),
+            days: vec![Day::new(2), Day::new(3)],
+            jobs: Some(4),
+            session_file: Some(PathBuf::from("session")),
+            params: vec![(Day::new(11), "long_game".to_string(), "50".to_string())],
+        };
+        let parse = |args: &[&str]| parse_args(args.iter().map(ToString::to_string), &config);
+
+        assert_eq!(
+            Ok(Command::Run(Selection {
+                days: vec![Day::new(2), Day::new(3)],
+                parts: Part::ALL.to_vec(),
+                input: InputSource::Real,
+                input_dir: Some(PathBuf::from("inputs")),
+                explicit_input_dir: false,
+                format: Some(Format::Json),
+                jobs: 4,
+                params: config.params.clone(),
+            })),
+            parse(&["--config", "other.toml"])
+        );
+        assert_eq!(
+            Ok(Command::Run(Selection {
+                days: vec![Day::new(1)],
+                parts: Part::ALL.to_vec(),
+                input: InputSource::Real,
+                input_dir: Some(PathBuf::from("res")),
+                explicit_input_dir: true,
+                format: None,
+                jobs: 1,
+                params: vec![
+                    (Day::new(11), "long_game".to_string(), "50".to_string()),
+                    (Day::new(11), "long_game".to_string(), "10".to_string()),
+                ],
+            })),
+            parse(&[
+                "1",
+                "-d",
+                "res",
+                "-f",
+                "text",
+                "-j",
+                "1",
+                "-P",
+                "11.long_game=10"
+            ])
+        );
+
+        let Ok(Command::Fetch(_, server)) = parse(&["fetch"]) else {
+            panic!("expected fetch mode");
+        };
+        assert_eq!(Some(PathBuf::from("session")), server.session_file);
+
+        let args = ["-C", "other.toml", "1"].map(String::from);
+        assert_eq!(PathBuf::from("other.toml"), config_path(&args));
+        assert_eq!(PathBuf::from("aoc.toml"), config_path(&args[2..]));
+    }
+
+    #[test]
+    fn parse_params() {
+        let Ok(Command::Bench(selection, _)) = parse(&[
+            "bench",
+            "-P",
+            "11.long_game=5000",
+            "--param",
+            "7.disk_size=",
+        ]) else {
+            panic!("expected a benchmark");
+        };
+
+        assert_eq!(
+            vec![
+                (Day::new(11), "long_game".to_string(), "5000".to_string()),
+                (Day::new(7), "disk_size".to_string(), String::new()),
+            ],
+            selection.params
+        );
+        assert!(parse(&["-P", "long_game=5000"]).is_err());
+        assert!(parse(&["-P", "11.long_game"]).is_err());
+        assert!(parse(&["-P", "11.=5"]).is_err());
+        assert!(parse(&["-P", "26.rounds=5"]).is_err());
+    }
+
+    #[test]
+    fn reject_bad_arguments() {
+        assert!(parse(&["26"]).is_err());
+        assert!(parse(&["0"]).is_err());
+        assert!(parse(&["5-3"]).is_err());
+        assert!(parse(&["1", "--part", "3"]).is_err());
+        assert!(parse(&["1-2", "--input", "a.txt"]).is_err());
+        assert!(parse(&["--frobnicate"]).is_err());
+        assert!(parse(&["--format", "yaml"]).is_err());
+        assert!(parse(&["--jobs", "0"]).is_err());
+    }
+}
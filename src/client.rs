@@ -0,0 +1,655 @@
+use std::{
+    fmt, fs,
+    io::{self, Read, Write},
+    net::TcpStream,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{Day, InputSource};
+
+/// Environment variable holding the session cookie of a logged-in Advent of Code account.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// Where the session token is read from if [`SESSION_VAR`] is not set.
+pub const DEFAULT_SESSION_FILE: &str = ".aoc_session";
+
+/// Where the time of the last request is kept, next to the session file, so that separate
+/// runs are spaced apart too.
+pub const LAST_REQUEST_FILE: &str = ".aoc_last_request";
+
+/// Environment variable that overrides [`DEFAULT_BASE_URL`].
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// The shortest time between two requests of one [`Client`] unless configured otherwise.
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The Advent of Code event the puzzles belong to.
+pub(crate) const YEAR: u16 = 2022;
+
+const USER_AGENT: &str = concat!("aoc2022/", env!("CARGO_PKG_VERSION"));
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A request to the puzzle server failed.
+#[derive(Debug)]
+pub enum ClientError {
+    /// Neither [`SESSION_VAR`] nor the session file holds a token.
+    NoSession(PathBuf),
+    /// The base URL is neither `http://` nor `https://`.
+    BadUrl(String),
+    /// HTTPS requests go through the `curl` executable, which is not installed.
+    NoCurl,
+    /// The server could not be reached, or the input could not be saved.
+    Io(io::Error),
+    /// The server answered with something other than `200 OK`.
+    Status { status: u16, body: String },
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSession(file) => write!(
+                f,
+                "no session token: set ${SESSION_VAR} or put it in {}",
+                file.display()
+            ),
+            Self::BadUrl(url) => write!(
+                f,
+                "base URL must start with http:// or https://. Got `{url}` instead."
+            ),
+            Self::NoCurl => write!(
+                f,
+                "HTTPS requests need `curl`, which was not found. Install it, or set \
+                 ${BASE_URL_VAR} to an http:// server."
+            ),
+            Self::Io(e) => write!(f, "{e}"),
+            Self::Status { status, body } => {
+                write!(f, "server answered {status}")?;
+                match body.lines().map(str::trim).find(|line| !line.is_empty()) {
+                    Some(line) => write!(f, ": {line}"),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl std::error::Error for ClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ClientError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Reads the session token from [`SESSION_VAR`], then from `file` or [`DEFAULT_SESSION_FILE`].
+///
+/// # Errors
+///
+/// Returns [`ClientError::NoSession`] if neither holds a token.
+pub fn session_token(file: Option<&Path>) -> Result<String, ClientError> {
+    if let Some(token) = std::env::var(SESSION_VAR)
+        .ok()
+        .filter(|token| !token.trim().is_empty())
+    {
+        return Ok(token.trim().to_owned());
+    }
+
+    let file = file.unwrap_or(Path::new(DEFAULT_SESSION_FILE));
+
+    fs::read_to_string(file)
+        .ok()
+        .map(|token| token.trim().to_owned())
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| ClientError::NoSession(file.to_owned()))
+}
+
+/// Where [`LAST_REQUEST_FILE`] is kept for the session token in `session_file`, or in
+/// [`DEFAULT_SESSION_FILE`].
+#[must_use]
+pub fn last_request_file(session_file: Option<&Path>) -> PathBuf {
+    session_file
+        .unwrap_or(Path::new(DEFAULT_SESSION_FILE))
+        .with_file_name(LAST_REQUEST_FILE)
+}
+
+/// A status code and body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+#[derive(Debug, Clone)]
+enum Transport {
+    /// Plain HTTP spoken directly, e.g. to a local stand-in server.
+    Plain { host: String, port: u16 },
+    /// HTTPS through the `curl` executable.
+    Curl,
+}
+
+/// Whether an input was downloaded or already on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fetched {
+    Downloaded(PathBuf),
+    Cached(PathBuf),
+}
+
+/// Talks to the Advent of Code website, or anything that answers the same requests.
+///
+/// Requests are spaced at least [`DEFAULT_INTERVAL`] apart. HTTPS requests need the `curl`
+/// executable.
+#[derive(Debug)]
+pub struct Client {
+    base_url: String,
+    transport: Transport,
+    session: String,
+    interval: Duration,
+    last_request: Option<SystemTime>,
+    last_request_file: Option<PathBuf>,
+}
+
+impl Client {
+    /// A client for the server at `base_url`, e.g. [`DEFAULT_BASE_URL`] or
+    /// `http://127.0.0.1:8080`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClientError::BadUrl`] if `base_url` is neither `http://` nor `https://`.
+    pub fn new(base_url: &str, session: impl Into<String>) -> Result<Self, ClientError> {
+        let base_url = base_url.trim_end_matches('/');
+
+        let transport = if let Some(rest) = base_url.strip_prefix("http://") {
+            let authority = rest.split('/').next().unwrap_or_default();
+            let (host, port) = match authority.rsplit_once(':') {
+                Some((host, port)) => (
+                    host,
+                    port.parse()
+                        .map_err(|_| ClientError::BadUrl(base_url.to_owned()))?,
+                ),
+                None => (authority, 80),
+            };
+            if host.is_empty() {
+                return Err(ClientError::BadUrl(base_url.to_owned()));
+            }
+            Transport::Plain {
+                host: host.to_owned(),
+                port,
+            }
+        } else if base_url.starts_with("https://") {
+            Transport::Curl
+        } else {
+            return Err(ClientError::BadUrl(base_url.to_owned()));
+        };
+
+        Ok(Self {
+            base_url: base_url.to_owned(),
+            transport,
+            session: session.into(),
+            interval: DEFAULT_INTERVAL,
+            last_request: None,
+            last_request_file: None,
+        })
+    }
+
+    /// Spaces requests at least `interval` apart instead of [`DEFAULT_INTERVAL`].
+    #[must_use]
+    pub fn with_interval(self, interval: Duration) -> Self {
+        Self { interval, ..self }
+    }
+
+    /// Keeps the time of the last request in `path`, so that clients in other processes using
+    /// the same file are spaced apart from this one.
+    #[must_use]
+    pub fn with_last_request_file(self, path: impl Into<PathBuf>) -> Self {
+        Self {
+            last_request_file: Some(path.into()),
+            ..self
+        }
+    }
+
+    /// Sends `GET` for `path`, e.g. `/2022/day/1/input`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClientError::Io`] if the server cannot be reached.
+    pub fn get(&mut self, path: &str) -> Result<Response, ClientError> {
+        self.send(path, None)
+    }
+
+    /// Sends `POST` for `path` with `form` as `application/x-www-form-urlencoded`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClientError::Io`] if the server cannot be reached.
+    pub fn post_form(
+        &mut self,
+        path: &str,
+        form: &[(&str, &str)],
+    ) -> Result<Response, ClientError> {
+        let body = form
+            .iter()
+            .map(|(name, value)| format!("{}={}", form_encode(name), form_encode(value)))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        self.send(path, Some(&body))
+    }
+
+    /// Downloads the real input of `day` to `day_N.txt` in `dir`, unless it is already there,
+    /// or, with the `encrypted-inputs` feature, its encrypted copy is.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the input cannot be saved.
+    pub fn fetch_input(&mut self, day: Day, dir: &Path) -> Result<Fetched, ClientError> {
+        let path = dir.join(InputSource::Real.file_name(day).unwrap_or_default());
+
+        if path.exists() {
+            return Ok(Fetched::Cached(path));
+        }
+        #[cfg(feature = "encrypted-inputs")]
+        if crate::encrypted_path(&path).exists() {
+            return Ok(Fetched::Cached(crate::encrypted_path(&path)));
+        }
+
+        let response = self.get(&format!("/{YEAR}/day/{day}/input"))?;
+        if response.status != 200 {
+            return Err(ClientError::Status {
+                status: response.status,
+                body: response.body,
+            });
+        }
+
+        // Write next to the target first so an interrupted download is never cached.
+        let partial = path.with_extension("txt.part");
+        fs::create_dir_all(dir)?;
+        fs::write(&partial, response.body)?;
+        fs::rename(&partial, &path)?;
+
+        Ok(Fetched::Downloaded(path))
+    }
+
+    /// Downloads the puzzle description of `day`, including part two once part one is solved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails.
+    pub fn puzzle_page(&mut self, day: Day) -> Result<String, ClientError> {
+        let response = self.get(&format!("/{YEAR}/day/{day}"))?;
+
+        if response.status == 200 {
+            Ok(response.body)
+        } else {
+            Err(ClientError::Status {
+                status: response.status,
+                body: response.body,
+            })
+        }
+    }
+
+    fn send(&mut self, path: &str, body: Option<&str>) -> Result<Response, ClientError> {
+        let last_request = self
+            .last_request_file
+            .as_deref()
+            .and_then(read_last_request)
+            .max(self.last_request);
+        // A time in the future counts as just now.
+        if let Some(wait) = last_request.and_then(|last| {
+            self.interval
+                .checked_sub(last.elapsed().unwrap_or_default())
+        }) {
+            thread::sleep(wait);
+        }
+        let now = SystemTime::now();
+        self.last_request = Some(now);
+        if let Some(file) = &self.last_request_file {
+            let nanos = now
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos();
+            fs::write(file, format!("{nanos}\n"))?;
+        }
+
+        match &self.transport {
+            Transport::Plain { host, port } => {
+                let prefix = self.base_url["http://".len()..]
+                    .find('/')
+                    .map_or("", |idx| &self.base_url["http://".len() + idx..]);
+                Ok(send_plain(
+                    host,
+                    *port,
+                    &format!("{prefix}{path}"),
+                    &self.session,
+                    body,
+                )?)
+            }
+            Transport::Curl => send_curl(&format!("{}{path}", self.base_url), &self.session, body),
+        }
+    }
+}
+
+fn read_last_request(file: &Path) -> Option<SystemTime> {
+    let nanos = fs::read_to_string(file).ok()?.trim().parse().ok()?;
+
+    UNIX_EPOCH.checked_add(Duration::from_nanos(nanos))
+}
+
+fn send_plain(
+    host: &str,
+    port: u16,
+    path: &str,
+    session: &str,
+    body: Option<&str>,
+) -> io::Result<Response> {
+    let mut stream = TcpStream::connect((host, port))?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+
+    // HTTP/1.0 keeps the response free of chunked encoding, and the server closes the
+    // connection once the body is sent.
+    let method = if body.is_some() { "POST" } else { "GET" };
+    let mut request = format!(
+        "{method} {path} HTTP/1.0\r\nHost: {host}:{port}\r\nUser-Agent: {USER_AGENT}\r\nCookie: session={session}\r\n"
+    );
+    if let Some(body) = body {
+        request += &format!(
+            "Content-Type: application/x-www-form-urlencoded\r\nContent-Length: {}\r\n",
+            body.len()
+        );
+    }
+    request += "\r\n";
+    request += body.unwrap_or_default();
+
+    stream.write_all(request.as_bytes())?;
+
+    let mut raw = Vec::new();
+    stream.read_to_end(&mut raw)?;
+
+    parse_response(&raw)
+}
+
+fn parse_response(raw: &[u8]) -> io::Result<Response> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "malformed HTTP response");
+
+    let split = raw
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or_else(invalid)?;
+    let head = std::str::from_utf8(&raw[..split]).map_err(|_| invalid())?;
+    let mut body = &raw[split + 4..];
+
+    let mut lines = head.lines();
+    let status = lines
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(invalid)?;
+
+    let content_length = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok());
+    if let Some(length) = content_length {
+        body = &body[..length.min(body.len())];
+    }
+
+    Ok(Response {
+        status,
+        body: String::from_utf8_lossy(body).into_owned(),
+    })
+}
+
+fn send_curl(url: &str, session: &str, body: Option<&str>) -> Result<Response, ClientError> {
+    let mut command = Command::new("curl");
+    // The cookie goes through stdin so the token does not show up in the process list.
+    command.args([
+        "--silent",
+        "--show-error",
+        "--max-time",
+        &TIMEOUT.as_secs().to_string(),
+        "--user-agent",
+        USER_AGENT,
+        "--header",
+        "@-",
+        "--write-out",
+        "\n%{http_code}",
+    ]);
+    if let Some(body) = body {
+        command.args(["--data", body]);
+    }
+    command
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Err(ClientError::NoCurl),
+        Err(e) => {
+            return Err(ClientError::Io(io::Error::new(
+                e.kind(),
+                format!("cannot run curl for HTTPS requests: {e}"),
+            )))
+        }
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "Cookie: session={session}")?;
+    }
+    let output = child.wait_with_output()?;
+
+    if !output.status.success() {
+        return Err(ClientError::Io(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        )));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = stdout
+        .rsplit_once('\n')
+        .and_then(|(body, status)| Some((body, status.trim().parse().ok()?)))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed curl output"))?;
+
+    Ok(Response {
+        status,
+        body: body.to_owned(),
+    })
+}
+
+fn form_encode(value: &str) -> String {
+    let mut encoded = String::new();
+
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~".contains(&byte) {
+            encoded.push(char::from(byte));
+        } else {
+            encoded += &format!("%{byte:02X}");
+        }
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{
+        fs,
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+        time::{Duration, Instant},
+    };
+
+    use crate::Day;
+
+    use super::{Client, ClientError, Fetched};
+
+    /// Answers one connection per response in `responses`, then returns every request.
+    pub(crate) fn serve(responses: Vec<String>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let server = thread::spawn(move || {
+            let mut requests = Vec::new();
+
+            for response in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut request = String::new();
+                let mut content_length = 0;
+
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(length) = line.strip_prefix("Content-Length: ") {
+                        content_length = length.trim().parse().unwrap();
+                    }
+                    request += &line;
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                request += &String::from_utf8(body).unwrap();
+
+                let mut stream = reader.into_inner();
+                stream.write_all(response.as_bytes()).unwrap();
+                requests.push(request);
+            }
+
+            requests
+        });
+
+        (base_url, server)
+    }
+
+    pub(crate) fn ok(body: &str) -> String {
+        format!(
+            "HTTP/1.0 200 OK\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+    }
+
+    #[test]
+    fn fetch_once_then_use_the_cache() {
+        let (base_url, server) = serve(vec![ok("1000\n2000\n")]);
+        let dir = std::env::temp_dir().join(format!("aoc_fetch_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut client = Client::new(&format!("{base_url}/"), "secret").unwrap();
+        let path = dir.join("day_1.txt");
+
+        assert_eq!(
+            Fetched::Downloaded(path.clone()),
+            client.fetch_input(Day::new(1), &dir).unwrap()
+        );
+        assert_eq!(
+            Fetched::Cached(path.clone()),
+            client.fetch_input(Day::new(1), &dir).unwrap()
+        );
+        assert_eq!("1000\n2000\n", fs::read_to_string(&path).unwrap());
+
+        let requests = server.join().unwrap();
+        assert_eq!(1, requests.len());
+        assert!(requests[0].starts_with("GET /2022/day/1/input HTTP/1.0\r\n"));
+        assert!(requests[0].contains("\r\nCookie: session=secret\r\n"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "encrypted-inputs")]
+    #[test]
+    fn encrypted_inputs_count_as_cached() {
+        let dir = std::env::temp_dir().join(format!("aoc_fetch_enc_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let encrypted = dir.join("day_1.txt.enc");
+        fs::write(&encrypted, "").unwrap();
+
+        // Nothing listens here, so any request would fail.
+        let mut client = Client::new("http://127.0.0.1:9/", "secret").unwrap();
+        let fetched = client.fetch_input(Day::new(1), &dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(Fetched::Cached(encrypted), fetched.unwrap());
+    }
+
+    #[test]
+    fn failed_downloads_are_not_cached() {
+        let (base_url, server) = serve(vec![
+            "HTTP/1.0 404 Not Found\r\n\r\nPlease don't repeatedly request this endpoint before it unlocks!\n"
+                .to_string(),
+        ]);
+        let dir = std::env::temp_dir().join(format!("aoc_fetch_404_{}", std::process::id()));
+
+        let mut client = Client::new(&format!("{base_url}/prefix"), "secret").unwrap();
+        let error = client.fetch_input(Day::new(25), &dir).unwrap_err();
+
+        assert!(matches!(error, ClientError::Status { status: 404, .. }));
+        assert!(error.to_string().contains("before it unlocks"));
+        assert!(!dir.join("day_25.txt").exists());
+        assert!(server.join().unwrap()[0].starts_with("GET /prefix/2022/day/25/input "));
+    }
+
+    #[test]
+    fn space_out_requests() {
+        let (base_url, server) = serve(vec![ok(""), ok("")]);
+
+        let mut client = Client::new(&base_url, "secret")
+            .unwrap()
+            .with_interval(Duration::from_millis(200));
+        let start = Instant::now();
+
+        client.get("/").unwrap();
+        client.post_form("/", &[("answer", "a b&c")]).unwrap();
+
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        assert!(server.join().unwrap()[1].ends_with("\r\n\r\nanswer=a%20b%26c"));
+    }
+
+    #[test]
+    fn space_out_requests_across_clients() {
+        let (base_url, server) = serve(vec![ok(""), ok("")]);
+        let file = std::env::temp_dir().join(format!("aoc_last_request_{}", std::process::id()));
+        let client = || {
+            Client::new(&base_url, "secret")
+                .unwrap()
+                .with_interval(Duration::from_millis(200))
+                .with_last_request_file(&file)
+        };
+        let start = Instant::now();
+
+        client().get("/").unwrap();
+        client().get("/").unwrap();
+
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        assert_eq!(2, server.join().unwrap().len());
+        fs::remove_file(&file).unwrap();
+    }
+
+    #[test]
+    fn reject_unsupported_urls() {
+        assert!(matches!(
+            Client::new("ftp://adventofcode.com", ""),
+            Err(ClientError::BadUrl(_))
+        ));
+        assert!(matches!(
+            Client::new("http://localhost:port", ""),
+            Err(ClientError::BadUrl(_))
+        ));
+        assert!(Client::new("https://adventofcode.com", "").is_ok());
+    }
+}
@@ -0,0 +1,352 @@
+use std::{
+    fs, io,
+    iter::Peekable,
+    path::{Path, PathBuf},
+    str::Chars,
+};
+
+use crate::{Day, Format};
+
+/// Where the runner looks for its configuration, relative to the working directory.
+pub const DEFAULT_CONFIG_FILE: &str = "aoc.toml";
+
+/// Project defaults for the runner, read from an `aoc.toml` file. Command-line flags take
+/// precedence over every setting.
+///
+/// Only the subset of TOML the settings need is understood:
+///
+/// ```toml
+/// input_dir = "inputs"
+/// format = "json"          # text, json, csv or markdown
+/// days = [1, 2, 3]         # the default selection, not a limit on which days can run
+/// jobs = 4
+/// session_file = "secrets/aoc_session"
+///
+/// [params.11]
+/// long_game = 5_000
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub input_dir: Option<PathBuf>,
+    /// `None` prints human-readable text.
+    pub format: Option<Format>,
+    /// The days to run when none are given on the command line, empty for every solved day.
+    /// Other days can still be run by naming them.
+    pub days: Vec<Day>,
+    pub jobs: Option<usize>,
+    pub session_file: Option<PathBuf>,
+    /// Solver parameter overrides as `(day, name, value)`, in file order.
+    pub params: Vec<(Day, String, String)>,
+}
+
+impl Config {
+    /// Reads the configuration at `path`. A missing file is the default configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, is not valid for the supported subset of
+    /// TOML, or sets an unknown key.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e),
+        };
+
+        Self::parse(&text).map_err(|(line, message)| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line {line}: {message}"),
+            )
+        })
+    }
+
+    /// Parses the text of a configuration file, returning the line and description of the
+    /// first error.
+    fn parse(text: &str) -> Result<Self, (usize, String)> {
+        let mut config = Self::default();
+        let mut table = None;
+
+        for (idx, line) in text.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[') {
+                let header = header
+                    .strip_suffix(']')
+                    .ok_or((idx + 1, "unclosed table".into()))?;
+                table = Some(parse_table(header.trim()).map_err(|e| (idx + 1, e))?);
+                continue;
+            }
+
+            config.set(table, line).map_err(|e| (idx + 1, e))?;
+        }
+
+        Ok(config)
+    }
+
+    /// Applies one `key = value` line, inside the `[params.N]` table of `table` if any.
+    fn set(&mut self, table: Option<Day>, line: &str) -> Result<(), String> {
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("expected `key = value`. Got `{line}` instead."))?;
+        let key = key.trim();
+        let value = parse_value(value.trim())?;
+
+        if let Some(day) = table {
+            let value = match value {
+                Value::String(text) => text,
+                Value::Integer(n) => n.to_string(),
+                Value::Boolean(b) => b.to_string(),
+                Value::Array(_) => return Err(format!("`{key}` cannot be an array")),
+            };
+            self.params.push((day, key.to_owned(), value));
+            return Ok(());
+        }
+
+        match key {
+            "input_dir" => self.input_dir = Some(value.into_string(key)?.into()),
+            "session_file" => self.session_file = Some(value.into_string(key)?.into()),
+            "format" => {
+                let format = value.into_string(key)?;
+                self.format = if format == "text" {
+                    None
+                } else {
+                    Some(format.parse()?)
+                };
+            }
+            "jobs" => {
+                let jobs = value.into_integer(key)?;
+                match usize::try_from(jobs) {
+                    Ok(jobs) if jobs > 0 => self.jobs = Some(jobs),
+                    _ => return Err(format!("`jobs` must be at least 1. Got {jobs} instead.")),
+                }
+            }
+            "days" => {
+                let Value::Array(days) = value else {
+                    return Err("`days` must be an array of days".to_string());
+                };
+                self.days = days
+                    .into_iter()
+                    .map(|day| {
+                        let day = day.into_integer(key)?;
+                        u8::try_from(day)
+                            .ok()
+                            .and_then(Day::try_new)
+                            .ok_or_else(|| {
+                                format!("day must be between 1 - 25. Got {day} instead.")
+                            })
+                    })
+                    .collect::<Result<_, _>>()?;
+                self.days.sort_unstable();
+                self.days.dedup();
+            }
+            _ => return Err(format!("unknown key `{key}`")),
+        }
+
+        Ok(())
+    }
+}
+
+/// The day of a `[params.N]` table header.
+fn parse_table(header: &str) -> Result<Day, String> {
+    header
+        .strip_prefix("params.")
+        .map(|day| day.trim_matches('"'))
+        .and_then(|day| day.parse().ok())
+        .and_then(Day::try_new)
+        .ok_or_else(|| format!("unknown table `[{header}]`, expected `[params.DAY]`"))
+}
+
+/// `line` without a trailing `#` comment, leaving `#` inside strings alone.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+
+    for (idx, c) in line.char_indices() {
+        match (c, quote) {
+            (_, Some('"')) if escaped => escaped = false,
+            ('\\', Some('"')) => escaped = true,
+            ('"' | '\'', None) => quote = Some(c),
+            (_, Some(open)) if c == open => quote = None,
+            ('#', None) => return &line[..idx],
+            _ => {}
+        }
+    }
+
+    line
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    fn into_string(self, key: &str) -> Result<String, String> {
+        match self {
+            Self::String(text) => Ok(text),
+            _ => Err(format!("`{key}` must be a string")),
+        }
+    }
+
+    fn into_integer(self, key: &str) -> Result<i64, String> {
+        match self {
+            Self::Integer(n) => Ok(n),
+            _ => Err(format!("`{key}` must be an integer")),
+        }
+    }
+}
+
+fn parse_value(text: &str) -> Result<Value, String> {
+    let mut chars = text.chars().peekable();
+    let value = next_value(&mut chars)?;
+
+    if chars.any(|c| !c.is_whitespace()) {
+        return Err(format!("unexpected text after the value `{text}`"));
+    }
+
+    Ok(value)
+}
+
+fn next_value(chars: &mut Peekable<Chars<'_>>) -> Result<Value, String> {
+    skip_whitespace(chars);
+
+    match chars.peek() {
+        Some('"') => {
+            chars.next();
+            let mut text = String::new();
+            loop {
+                match chars.next().ok_or("unclosed string")? {
+                    '"' => break Ok(Value::String(text)),
+                    '\\' => text.push(match chars.next().ok_or("unclosed string")? {
+                        'n' => '\n',
+                        't' => '\t',
+                        c @ ('"' | '\\') => c,
+                        c => return Err(format!("unsupported escape `\\{c}`")),
+                    }),
+                    c => text.push(c),
+                }
+            }
+        }
+        Some('\'') => {
+            chars.next();
+            let text = chars.by_ref().take_while(|&c| c != '\'').collect();
+            Ok(Value::String(text))
+        }
+        Some('[') => {
+            chars.next();
+            let mut values = Vec::new();
+            loop {
+                skip_whitespace(chars);
+                if chars.next_if_eq(&']').is_some() {
+                    break Ok(Value::Array(values));
+                }
+                values.push(next_value(chars)?);
+                skip_whitespace(chars);
+                match chars.next() {
+                    Some(',') => {}
+                    Some(']') => break Ok(Value::Array(values)),
+                    _ => break Err("unclosed array".to_string()),
+                }
+            }
+        }
+        _ => {
+            let mut word = String::new();
+            while let Some(c) = chars.next_if(|&c| c.is_alphanumeric() || "+-_".contains(c)) {
+                word.push(c);
+            }
+
+            match word.as_str() {
+                "true" => Ok(Value::Boolean(true)),
+                "false" => Ok(Value::Boolean(false)),
+                _ => word
+                    .replace('_', "")
+                    .parse()
+                    .map(Value::Integer)
+                    .map_err(|_| {
+                        format!(
+                            "expected a string, integer, boolean or array. Got `{word}` instead."
+                        )
+                    }),
+            }
+        }
+    }
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars<'_>>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::{Day, Format};
+
+    use super::Config;
+
+    #[test]
+    fn read_every_setting() {
+        let config = Config::parse(
+            r#"
+# Project defaults
+input_dir = "inputs"   # next to the sources
+format = "json"
+days = [ 3, 1, 2, ]
+jobs = 4
+session_file = 'C:\aoc\session'
+
+[params.11]
+long_game = 5_000
+relief = "1"
+
+[params."7"]
+disk_size = 100
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            Config {
+                input_dir: Some(PathBuf::from("inputs")),
+                format: Some(Format::Json),
+                days: vec![Day::new(1), Day::new(2), Day::new(3)],
+                jobs: Some(4),
+                session_file: Some(PathBuf::from(r"C:\aoc\session")),
+                params: vec![
+                    (Day::new(11), "long_game".to_string(), "5000".to_string()),
+                    (Day::new(11), "relief".to_string(), "1".to_string()),
+                    (Day::new(7), "disk_size".to_string(), "100".to_string()),
+                ],
+            },
+            config
+        );
+        assert_eq!(Ok(Config::default()), Config::parse("format = \"text\"\n"));
+    }
+
+    #[test]
+    fn reject_malformed_configs() {
+        for (text, line) in [
+            ("jobs = 0", 1),
+            ("\ninput_dir = inputs", 2),
+            ("days = [1, 26]", 1),
+            ("format = \"yaml\"", 1),
+            ("color = true", 1),
+            ("[runner]\njobs = 2", 1),
+            ("[params.3]\nlimit = [1]", 2),
+            ("session_file = \"unclosed", 1),
+        ] {
+            assert_eq!(
+                Some(line),
+                Config::parse(text).err().map(|(line, _)| line),
+                "{text}"
+            );
+        }
+    }
+}
@@ -1,76 +1,104 @@
-use std::io::BufRead;
+//! Day 1: Calorie Counting.
 
-use crate::{get_day_input, AOCSolver, Day, Part};
+use crate::{
+    error::Line, get_day_input, read_lines, registry::Registry, AOCSolver, Answer, AocError, Day,
+    InputSource,
+};
 
+/// Solves both parts of day 1 through [`AOCSolver`].
 pub struct Solver;
 
 impl AOCSolver for Solver {
-    type Solution = u64;
+    type Input = Vec<u64>;
+    type Params = Params;
 
-    fn solve(part: Part) -> Self::Solution {
-        if let Part::One = part {
-            solve_part_one()
-        } else {
-            solve_part_two()
-        }
+    fn parse(source: &InputSource) -> Result<Self::Input, AocError> {
+        parse_elves(source)
+    }
+
+    fn part_one(input: &Self::Input, _: &Self::Params) -> Result<Answer, AocError> {
+        solve_part_one(input).map(Answer::from)
+    }
+
+    fn part_two(input: &Self::Input, params: &Self::Params) -> Result<Answer, AocError> {
+        solve_part_two(input, params).map(Answer::from)
+    }
+}
+
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add(Day::new(1), Solver);
+}
+
+params! {
+    /// Tunable values for day 1.
+    pub struct Params(1) {
+        /// How many of the best-stocked elves part two adds up.
+        top: usize = 3,
     }
 }
 
-fn parse_elves(load_sample: bool) -> Vec<u64> {
-    let file = get_day_input(Day::new(1), load_sample);
-    let reader = std::io::BufReader::new(file);
+/// Reads the calories carried by each elf, summed per elf.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read or a line is malformed.
+pub fn parse_elves(source: &InputSource) -> Result<Vec<u64>, AocError> {
+    let day = Day::new(1);
+    let file = get_day_input(day, source)?;
 
     let mut summed_values = Vec::new();
 
     let mut sum = 0;
 
-    for line in reader.lines().flatten() {
-        if line.is_empty() {
+    for (idx, text) in read_lines(day, file).enumerate() {
+        let text = text?;
+        let line = Line::new(day, idx, &text);
+
+        if text.is_empty() {
             summed_values.push(sum);
             sum = 0;
         } else {
-            sum += line.parse::<u64>().expect("Parsing value");
+            sum += line.parse::<u64>(line.text())?;
         }
     }
 
     summed_values.push(sum);
 
-    summed_values
+    Ok(summed_values)
 }
 
-fn solve_part_one() -> u64 {
-    let summed_values = parse_elves(false);
-
-    *summed_values.iter().max().unwrap()
+/// The most calories carried by a single elf.
+pub fn solve_part_one(summed_values: &[u64]) -> Result<u64, AocError> {
+    Ok(summed_values.iter().max().copied().unwrap_or_default())
 }
 
-fn solve_part_two() -> u64 {
-    let mut summed_values = parse_elves(false);
+/// The calories carried by the [`Params::top`] elves carrying the most.
+pub fn solve_part_two(summed_values: &[u64], params: &Params) -> Result<u64, AocError> {
+    let mut summed_values = summed_values.to_vec();
 
     summed_values.sort_unstable_by(|x, y| y.cmp(x));
 
-    summed_values[0] + summed_values[1] + summed_values[2]
+    Ok(summed_values.into_iter().take(params.top).sum())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::d01::parse_elves;
+    use crate::{AOCSolver, InputSource};
 
-    #[test]
-    fn solve_sample_one() {
-        let summed_values = parse_elves(true);
+    use super::{Params, Solver};
 
-        assert_eq!(Some(24000), summed_values.iter().max().copied());
+    sample_tests! {
+        Solver;
+        sample: InputSource::Sample => { One: 24000, Two: 45000 },
     }
 
     #[test]
-    fn solve_sample_two() {
-        let mut summed_values = parse_elves(true);
-
-        summed_values.sort_unstable_by(|x, y| y.cmp(x));
-
-        let max_three_sum = summed_values[0] + summed_values[1] + summed_values[2];
+    fn sum_the_top_five() {
+        let elves = Solver::parse(&InputSource::Sample).unwrap();
 
-        assert_eq!(45000, max_three_sum);
+        assert_eq!(
+            55000,
+            super::solve_part_two(&elves, &Params { top: 5 }).unwrap()
+        );
     }
 }
@@ -1,21 +1,34 @@
-use std::io::BufRead;
+//! Day 2: Rock Paper Scissors.
 
-use crate::{get_day_input, AOCSolver, Day, Part};
+use crate::{
+    error::Line, get_day_input, read_lines, registry::Registry, AOCSolver, Answer, AocError, Day,
+    InputSource,
+};
 
+/// Solves both parts of day 2 through [`AOCSolver`].
 pub struct Solver;
 
 impl AOCSolver for Solver {
-    type Solution = u64;
+    type Input = Vec<Strategy>;
+    type Params = ();
 
-    fn solve(part: Part) -> Self::Solution {
-        if let Part::One = part {
-            solve_part_one()
-        } else {
-            solve_part_two()
-        }
+    fn parse(source: &InputSource) -> Result<Self::Input, AocError> {
+        parse_strategies(source)
+    }
+
+    fn part_one(input: &Self::Input, _: &()) -> Result<Answer, AocError> {
+        solve_part_one(input).map(Answer::from)
+    }
+
+    fn part_two(input: &Self::Input, _: &()) -> Result<Answer, AocError> {
+        solve_part_two(input).map(Answer::from)
     }
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add(Day::new(2), Solver);
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 enum Weapon {
     Rock = 1,
@@ -24,12 +37,12 @@ enum Weapon {
 }
 
 impl Weapon {
-    fn as_weapon(x: &str) -> Self {
+    fn as_weapon(line: &Line, x: &str) -> Result<Self, AocError> {
         match x {
-            "A" | "X" => Self::Rock,
-            "B" | "Y" => Self::Paper,
-            "C" | "Z" => Self::Scissors,
-            _ => panic!("{x} is not a valid move"),
+            "A" | "X" => Ok(Self::Rock),
+            "B" | "Y" => Ok(Self::Paper),
+            "C" | "Z" => Ok(Self::Scissors),
+            _ => Err(line.error(x, "expected a move (A/B/C or X/Y/Z)")),
         }
     }
 }
@@ -42,17 +55,49 @@ enum End {
 }
 
 impl End {
-    fn as_end(x: &str) -> Self {
+    const fn as_end(response: Response) -> Self {
+        match response {
+            Response::X => Self::Loss,
+            Response::Y => Self::Draw,
+            Response::Z => Self::Win,
+        }
+    }
+}
+
+/// The second column of the strategy guide, read as a move in part one and an outcome in part two.
+#[derive(Clone, Copy)]
+pub enum Response {
+    X,
+    Y,
+    Z,
+}
+
+impl Response {
+    fn as_response(line: &Line, x: &str) -> Result<Self, AocError> {
         match x {
-            "X" => Self::Loss,
-            "Y" => Self::Draw,
-            "Z" => Self::Win,
-            _ => panic!("{x} is not a valid move"),
+            "X" => Ok(Self::X),
+            "Y" => Ok(Self::Y),
+            "Z" => Ok(Self::Z),
+            _ => Err(line.error(x, "expected a response (X/Y/Z)")),
+        }
+    }
+
+    const fn as_weapon(self) -> Weapon {
+        match self {
+            Self::X => Weapon::Rock,
+            Self::Y => Weapon::Paper,
+            Self::Z => Weapon::Scissors,
         }
     }
 }
 
-pub struct Game {
+/// One round of the strategy guide: the opponent's move and the suggested response.
+pub struct Strategy {
+    opponent: Weapon,
+    response: Response,
+}
+
+struct Game {
     weapon: Weapon,
     end: End,
 }
@@ -63,17 +108,35 @@ impl Game {
     }
 }
 
-fn parse_games_by_attack(load_sample: bool) -> Vec<Game> {
-    let file = get_day_input(Day::new(2), load_sample);
-    let reader = std::io::BufReader::new(file);
+/// Reads one [`Strategy`] per line.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read or a line is malformed.
+pub fn parse_strategies(source: &InputSource) -> Result<Vec<Strategy>, AocError> {
+    let day = Day::new(2);
+    let file = get_day_input(day, source)?;
+
+    let mut strategies = Vec::new();
+
+    for (idx, text) in read_lines(day, file).enumerate() {
+        let text = text?;
+        let line = Line::new(day, idx, &text);
+
+        strategies.push(Strategy {
+            opponent: Weapon::as_weapon(&line, line.field(0, "the opponent's move")?)?,
+            response: Response::as_response(&line, line.field(1, "a response")?)?,
+        });
+    }
+
+    Ok(strategies)
+}
 
+fn games_by_attack(strategies: &[Strategy]) -> Vec<Game> {
     let mut parsed_games = Vec::new();
 
-    for line in reader.lines().flatten() {
-        let xs = line
-            .split_whitespace()
-            .map(Weapon::as_weapon)
-            .collect::<Vec<_>>();
+    for strategy in strategies {
+        let xs = [strategy.opponent, strategy.response.as_weapon()];
         let end_game = match (xs[1], xs[0]) {
             (Weapon::Rock, Weapon::Paper)
             | (Weapon::Paper, Weapon::Scissors)
@@ -97,16 +160,11 @@ fn parse_games_by_attack(load_sample: bool) -> Vec<Game> {
     parsed_games
 }
 
-fn parse_games_by_end_state(load_sample: bool) -> Vec<Game> {
-    let file = get_day_input(Day::new(2), load_sample);
-    let reader = std::io::BufReader::new(file);
-
+fn games_by_end_state(strategies: &[Strategy]) -> Vec<Game> {
     let mut parsed_games = Vec::new();
 
-    for line in reader.lines().flatten() {
-        let xs = line.split_whitespace().collect::<Vec<_>>();
-
-        let (weapon, end_game) = (Weapon::as_weapon(xs[0]), End::as_end(xs[1]));
+    for strategy in strategies {
+        let (weapon, end_game) = (strategy.opponent, End::as_end(strategy.response));
 
         let end_weapon = match (weapon, end_game) {
             (Weapon::Scissors, End::Win)
@@ -131,37 +189,26 @@ fn parse_games_by_end_state(load_sample: bool) -> Vec<Game> {
     parsed_games
 }
 
-fn solve_part_one() -> u64 {
-    let games = parse_games_by_attack(false);
+/// The total score when the second column is the move to play.
+pub fn solve_part_one(strategies: &[Strategy]) -> Result<u64, AocError> {
+    let games = games_by_attack(strategies);
 
-    games.into_iter().map(Game::score).sum()
+    Ok(games.into_iter().map(Game::score).sum())
 }
 
-fn solve_part_two() -> u64 {
-    let games = parse_games_by_end_state(false);
+/// The total score when the second column is the outcome to aim for.
+pub fn solve_part_two(strategies: &[Strategy]) -> Result<u64, AocError> {
+    let games = games_by_end_state(strategies);
 
-    games.into_iter().map(Game::score).sum()
+    Ok(games.into_iter().map(Game::score).sum())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::d02::{parse_games_by_attack, parse_games_by_end_state, Game};
-
-    #[test]
-    fn solve_sample_one() {
-        let games = parse_games_by_attack(true);
-
-        let val = games.into_iter().map(Game::score).sum::<u64>();
-
-        assert_eq!(15u64, val);
-    }
-
-    #[test]
-    fn solve_sample_two() {
-        let games = parse_games_by_end_state(true);
-
-        let val = games.into_iter().map(Game::score).sum::<u64>();
+    use crate::InputSource;
 
-        assert_eq!(12u64, val);
+    sample_tests! {
+        super::Solver;
+        sample: InputSource::Sample => { One: 15, Two: 12 },
     }
 }
@@ -1,70 +1,101 @@
-use std::{collections::HashSet, io::BufRead};
+//! Day 3: Rucksack Reorganization.
 
-use crate::{get_day_input, AOCSolver, Day, Part};
+use std::collections::HashSet;
 
+use crate::{
+    error::Line, get_day_input, read_lines, registry::Registry, AOCSolver, Answer, AocError, Day,
+    InputSource,
+};
+
+/// Solves both parts of day 3 through [`AOCSolver`].
 pub struct Solver;
 
 impl AOCSolver for Solver {
-    type Solution = u64;
+    type Input = Vec<Rucksack>;
+    type Params = ();
 
-    fn solve(part: Part) -> Self::Solution {
-        if let Part::One = part {
-            solve_part_one()
-        } else {
-            solve_part_two()
-        }
+    fn parse(source: &InputSource) -> Result<Self::Input, AocError> {
+        parse_rucksacks(source)
+    }
+
+    fn part_one(input: &Self::Input, _: &()) -> Result<Answer, AocError> {
+        solve_part_one(input).map(Answer::from)
+    }
+
+    fn part_two(input: &Self::Input, _: &()) -> Result<Answer, AocError> {
+        solve_part_two(input).map(Answer::from)
+    }
+}
+
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add(Day::new(3), Solver);
+}
+
+fn priority(line: &Line, item: &str) -> Result<u8, AocError> {
+    match item.as_bytes() {
+        [ch @ b'a'..=b'z'] => Ok(ch - b'a' + 1),
+        [ch @ b'A'..=b'Z'] => Ok(ch - b'A' + 27),
+        _ => Err(line.error(item, "expected an item between a-z or A-Z")),
     }
 }
 
-fn parse_rucksacks(load_sample: bool) -> Vec<(HashSet<u8>, HashSet<u8>)> {
-    let file = get_day_input(Day::new(3), load_sample);
-    let reader = std::io::BufReader::new(file);
+/// The item priorities in each compartment of a rucksack.
+pub type Rucksack = (HashSet<u8>, HashSet<u8>);
+
+/// Reads one [`Rucksack`] per line, split into its two compartments.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read or a line is malformed.
+pub fn parse_rucksacks(source: &InputSource) -> Result<Vec<Rucksack>, AocError> {
+    let day = Day::new(3);
+    let file = get_day_input(day, source)?;
 
     let mut compartments = Vec::new();
 
     let (mut c1, mut c2) = (HashSet::new(), HashSet::new());
 
-    for line in reader.lines().flatten() {
-        let (a, b) = line.split_at(line.len() / 2);
-        for ch in a.chars() {
-            c1.insert(if ch.is_ascii_uppercase() {
-                ch as u8 - 65 + 27
-            } else {
-                ch as u8 - 97 + 1
-            });
+    for (idx, text) in read_lines(day, file).enumerate() {
+        let text = text?;
+        let line = Line::new(day, idx, &text);
+
+        if text.len() % 2 != 0 || !text.is_ascii() {
+            return Err(line.error(&text, "expected two equally sized compartments"));
         }
-        for ch in b.chars() {
-            c2.insert(if ch.is_ascii_uppercase() {
-                ch as u8 - 65 + 27
-            } else {
-                ch as u8 - 97 + 1
-            });
+
+        let (a, b) = text.split_at(text.len() / 2);
+        for item in a.matches(|_| true) {
+            c1.insert(priority(&line, item)?);
+        }
+        for item in b.matches(|_| true) {
+            c2.insert(priority(&line, item)?);
         }
         compartments.push((c1.clone(), c2.clone()));
         c1.clear();
         c2.clear();
     }
 
-    compartments
+    Ok(compartments)
 }
 
-fn solve_part_one() -> u64 {
-    let compartments = parse_rucksacks(false);
-
-    compartments.into_iter().fold(0, |acc, (c1, c2)| {
-        let cs = c1.intersection(&c2).collect::<Vec<_>>();
-        acc + u64::from(*cs[0])
+/// The summed priority of the item found in both compartments of each rucksack.
+pub fn solve_part_one(compartments: &[Rucksack]) -> Result<u64, AocError> {
+    compartments.iter().try_fold(0, |acc, (c1, c2)| {
+        let cs = c1.intersection(c2).collect::<Vec<_>>();
+        let shared = cs.first().ok_or_else(|| {
+            AocError::no_solution(Day::new(3), "a rucksack has no item in both compartments")
+        })?;
+        Ok(acc + u64::from(**shared))
     })
 }
 
-fn solve_part_two() -> u64 {
-    let compartments = parse_rucksacks(false);
-
+/// The summed priority of the badge shared by each group of three elves.
+pub fn solve_part_two(compartments: &[Rucksack]) -> Result<u64, AocError> {
     let mut grouped_compartments = Vec::new();
 
     let mut group = Vec::new();
 
-    for (idx, (c1, c2)) in compartments.into_iter().enumerate() {
+    for (idx, (c1, c2)) in compartments.iter().enumerate() {
         group.push((c1, c2));
         if idx % 3 == 2 {
             grouped_compartments.push(group.clone());
@@ -78,7 +109,7 @@ fn solve_part_two() -> u64 {
         let mut badges: HashSet<u8> = HashSet::new();
 
         for (c1, c2) in group {
-            let cs = c1.union(&c2).copied().collect::<HashSet<_>>();
+            let cs = c1.union(c2).copied().collect::<HashSet<_>>();
             if badges.is_empty() {
                 badges = cs;
             } else {
@@ -89,60 +120,15 @@ fn solve_part_two() -> u64 {
         summed_groups += badges.into_iter().map(u64::from).sum::<u64>();
     }
 
-    summed_groups
+    Ok(summed_groups)
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
-
-    use crate::d03::parse_rucksacks;
-
-    #[test]
-    fn solve_sample_one() {
-        let compartments = parse_rucksacks(true);
-
-        let summed_priorities = compartments.into_iter().fold(0, |acc, (c1, c2)| {
-            let cs = c1.intersection(&c2).collect::<Vec<_>>();
-            acc + u64::from(*cs[0])
-        });
-
-        assert_eq!(157, summed_priorities);
-    }
-
-    #[test]
-    fn solve_sample_two() {
-        let compartments = parse_rucksacks(true);
-
-        let mut grouped_compartments = Vec::new();
-
-        let mut group = Vec::new();
-
-        for (idx, (c1, c2)) in compartments.into_iter().enumerate() {
-            group.push((c1, c2));
-            if idx % 3 == 2 {
-                grouped_compartments.push(group.clone());
-                group.clear();
-            }
-        }
-
-        let mut summed_groups = 0;
-
-        for group in grouped_compartments {
-            let mut badges = HashSet::new();
-
-            for (c1, c2) in group {
-                let cs = c1.union(&c2).copied().collect::<HashSet<_>>();
-                if badges.is_empty() {
-                    badges = cs;
-                } else {
-                    badges = badges.intersection(&cs).copied().collect::<HashSet<_>>();
-                }
-            }
-
-            summed_groups += badges.into_iter().map(u64::from).sum::<u64>();
-        }
+    use crate::InputSource;
 
-        assert_eq!(70, summed_groups);
+    sample_tests! {
+        super::Solver;
+        sample: InputSource::Sample => { One: 157, Two: 70 },
     }
 }
@@ -1,57 +1,82 @@
-use std::{io::BufRead, ops::RangeInclusive};
+//! Day 4: Camp Cleanup.
 
-use crate::{get_day_input, AOCSolver, Day, Part};
+use std::ops::RangeInclusive;
 
+use crate::{
+    error::Line, get_day_input, read_lines, registry::Registry, AOCSolver, Answer, AocError, Day,
+    InputSource,
+};
+
+/// Solves both parts of day 4 through [`AOCSolver`].
 pub struct Solver;
 
 impl AOCSolver for Solver {
-    type Solution = u64;
+    type Input = Vec<Assignments>;
+    type Params = ();
 
-    fn solve(part: Part) -> Self::Solution {
-        if let Part::One = part {
-            solve_part_one()
-        } else {
-            solve_part_two()
-        }
+    fn parse(source: &InputSource) -> Result<Self::Input, AocError> {
+        parse_assignments(source)
+    }
+
+    fn part_one(input: &Self::Input, _: &()) -> Result<Answer, AocError> {
+        solve_part_one(input).map(Answer::from)
+    }
+
+    fn part_two(input: &Self::Input, _: &()) -> Result<Answer, AocError> {
+        solve_part_two(input).map(Answer::from)
     }
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add(Day::new(4), Solver);
+}
+
+/// The section ranges assigned to a pair of elves.
 pub struct Assignments {
     e1: RangeInclusive<u64>,
     e2: RangeInclusive<u64>,
 }
 
-fn parse_assignments(load_sample: bool) -> Vec<Assignments> {
-    let file = get_day_input(Day::new(4), load_sample);
-    let reader = std::io::BufReader::new(file);
+fn parse_range(line: &Line, range: &str) -> Result<RangeInclusive<u64>, AocError> {
+    let (start, end) = range
+        .split_once('-')
+        .ok_or_else(|| line.error(range, "expected a range like `2-4`"))?;
+
+    Ok(line.parse(start)?..=line.parse(end)?)
+}
+
+/// Reads one pair of [`Assignments`] per line.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read or a line is malformed.
+pub fn parse_assignments(source: &InputSource) -> Result<Vec<Assignments>, AocError> {
+    let day = Day::new(4);
+    let file = get_day_input(day, source)?;
 
     let mut assignments = Vec::new();
 
-    for line in reader.lines().flatten() {
-        let ranges = line.split(',').collect::<Vec<&str>>();
-        let r1 = ranges[0]
-            .split('-')
-            .filter_map(|d| d.parse().ok())
-            .collect::<Vec<_>>();
-        let r2 = ranges[1]
-            .split('-')
-            .filter_map(|d| d.parse().ok())
-            .collect::<Vec<_>>();
+    for (idx, text) in read_lines(day, file).enumerate() {
+        let text = text?;
+        let line = Line::new(day, idx, &text);
+
+        let (r1, r2) = text
+            .split_once(',')
+            .ok_or_else(|| line.missing("two comma-separated ranges"))?;
 
         let assignment = Assignments {
-            e1: r1[0]..=r1[1],
-            e2: r2[0]..=r2[1],
+            e1: parse_range(&line, r1)?,
+            e2: parse_range(&line, r2)?,
         };
 
         assignments.push(assignment);
     }
 
-    assignments
+    Ok(assignments)
 }
 
-fn solve_part_one() -> u64 {
-    let assignments = parse_assignments(false);
-
+/// How many pairs have one range fully containing the other.
+pub fn solve_part_one(assignments: &[Assignments]) -> Result<u64, AocError> {
     let mut shared_assignments = 0;
 
     for assignment in assignments {
@@ -64,12 +89,11 @@ fn solve_part_one() -> u64 {
         }
     }
 
-    shared_assignments
+    Ok(shared_assignments)
 }
 
-fn solve_part_two() -> u64 {
-    let assignments = parse_assignments(false);
-
+/// How many pairs have overlapping ranges.
+pub fn solve_part_two(assignments: &[Assignments]) -> Result<u64, AocError> {
     let mut shared_assignments = 0;
 
     for assignment in assignments {
@@ -82,48 +106,15 @@ fn solve_part_two() -> u64 {
         }
     }
 
-    shared_assignments
+    Ok(shared_assignments)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::d04::parse_assignments;
-
-    #[test]
-    fn solve_sample_one() {
-        let assignments = parse_assignments(true);
-
-        let mut shared_assignments = 0;
-
-        for assignment in assignments {
-            if assignment.e1.contains(assignment.e2.start())
-                && assignment.e1.contains(assignment.e2.end())
-                || assignment.e2.contains(assignment.e1.start())
-                    && assignment.e2.contains(assignment.e1.end())
-            {
-                shared_assignments += 1;
-            }
-        }
-
-        assert_eq!(2, shared_assignments);
-    }
-
-    #[test]
-    fn solve_sample_two() {
-        let assignments = parse_assignments(true);
-
-        let mut shared_assignments = 0;
-
-        for assignment in assignments {
-            if assignment.e1.contains(assignment.e2.start())
-                || assignment.e1.contains(assignment.e2.end())
-                || assignment.e2.contains(assignment.e1.start())
-                || assignment.e2.contains(assignment.e1.end())
-            {
-                shared_assignments += 1;
-            }
-        }
+    use crate::InputSource;
 
-        assert_eq!(4, shared_assignments);
+    sample_tests! {
+        super::Solver;
+        sample: InputSource::Sample => { One: 2, Two: 4 },
     }
 }
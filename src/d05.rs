@@ -1,25 +1,39 @@
+//! Day 5: Supply Stacks.
+
 use std::{
     collections::VecDeque,
-    io::BufRead,
     iter::{IntoIterator, Iterator},
 };
 
-use crate::{get_day_input, AOCSolver, Day, Part};
+use crate::{
+    error::Line, get_day_input, read_lines, registry::Registry, AOCSolver, Answer, AocError, Day,
+    InputSource,
+};
 
+/// Solves both parts of day 5 through [`AOCSolver`].
 pub struct Solver;
 
 impl AOCSolver for Solver {
-    type Solution = String;
+    type Input = Schedule;
+    type Params = ();
 
-    fn solve(part: Part) -> Self::Solution {
-        if let Part::One = part {
-            solve_part_one()
-        } else {
-            solve_part_two()
-        }
+    fn parse(source: &InputSource) -> Result<Self::Input, AocError> {
+        parse_manifest(source)
+    }
+
+    fn part_one(input: &Self::Input, _: &()) -> Result<Answer, AocError> {
+        solve_part_one(input).map(Answer::from)
+    }
+
+    fn part_two(input: &Self::Input, _: &()) -> Result<Answer, AocError> {
+        solve_part_two(input).map(Answer::from)
     }
 }
 
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add(Day::new(5), Solver);
+}
+
 fn transpose(v: Vec<Vec<char>>) -> Vec<Vec<char>> {
     assert!(!v.is_empty());
     let len = v[0].len();
@@ -42,176 +56,175 @@ struct Instruction {
     move_amount: usize,
 }
 
+/// The starting crate stacks and the moves the crane makes.
 #[derive(Debug)]
-struct Schedule {
+pub struct Schedule {
     arrangement: Vec<Vec<char>>,
     instructions: Vec<Instruction>,
 }
 
-fn parse_manifest(load_sample: bool) -> Schedule {
-    let file = get_day_input(Day::new(5), load_sample);
-    let reader = std::io::BufReader::new(file);
-
-    let mut stacks = Vec::new();
+fn parse_crates(line: &Line) -> Result<Vec<char>, AocError> {
+    let text = line.text();
 
     let mut stack = Vec::new();
 
-    let mut section = true;
+    for (idx, char) in text.char_indices().skip(1).step_by(4) {
+        if char.is_ascii_uppercase() || char.is_ascii_whitespace() {
+            stack.push(char);
+        } else if !char.is_ascii_digit() {
+            return Err(line.error(&text[idx..idx + char.len_utf8()], "expected a crate"));
+        }
+    }
+
+    Ok(stack)
+}
 
-    let mut instructions = Vec::new();
+fn parse_instruction(line: &Line, num_stacks: usize) -> Result<Instruction, AocError> {
+    for (idx, keyword) in [(0, "move"), (2, "from"), (4, "to")] {
+        let field = line.field(idx, &format!("`{keyword}`"))?;
+        if field != keyword {
+            return Err(line.error(field, format!("expected `{keyword}`")));
+        }
+    }
 
-    for line in reader.lines().flatten() {
-        if line.is_empty() {
-            section = false;
-            continue;
+    let stack = |idx| -> Result<usize, AocError> {
+        let field = line.field(idx, "a stack number")?;
+        match line.parse::<usize>(field)? {
+            stack @ 1.. if stack <= num_stacks => Ok(stack - 1),
+            _ => Err(line.error(field, format!("expected a stack between 1 - {num_stacks}"))),
         }
-        if section {
-            for char in line.chars().skip(1).step_by(4) {
-                if char.is_ascii_uppercase() || char.is_ascii_whitespace() {
-                    stack.push(char);
+    };
+
+    Ok(Instruction {
+        source_stack: stack(3)?,
+        target_stack: stack(5)?,
+        move_amount: line.parse(line.field(1, "a crate count")?)?,
+    })
+}
+
+/// Reads the stack drawing followed by the rearrangement procedure.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read or a line is malformed.
+pub fn parse_manifest(source: &InputSource) -> Result<Schedule, AocError> {
+    let day = Day::new(5);
+    let file = get_day_input(day, source)?;
+
+    let mut stacks = Vec::new();
+
+    let mut arrangement = None;
+
+    let mut instructions = Vec::new();
+
+    for (idx, text) in read_lines(day, file).enumerate() {
+        let text = text?;
+        let line = Line::new(day, idx, &text);
+
+        match &arrangement {
+            None if text.is_empty() => {
+                if stacks.is_empty() {
+                    return Err(line.error(&text, "expected a drawing of the crate stacks"));
                 }
+                arrangement = Some(transpose(stacks.drain(..).rev().collect()));
             }
-            if !stack.is_empty() {
-                stacks.push(stack.clone());
-                stack.clear();
-            }
-        } else {
-            let mut values = Vec::new();
-
-            let mut value = String::new();
-
-            for char in line.chars().skip(5) {
-                if char.is_ascii_digit() {
-                    value.push(char);
-                } else if char.is_whitespace() {
-                    if value.is_empty() {
-                        continue;
-                    }
-                    let res = value.parse().unwrap();
-                    values.push(res);
-                    value.clear();
+            None => {
+                let stack = parse_crates(&line)?;
+                if !stack.is_empty() {
+                    stacks.push(stack);
                 }
             }
-            let instruction = Instruction {
-                source_stack: values[1] - 1,
-                target_stack: value.parse::<usize>().unwrap() - 1,
-                move_amount: values[0],
-            };
-            instructions.push(instruction);
+            Some(arrangement) => {
+                instructions.push(parse_instruction(&line, arrangement.len())?);
+            }
         }
     }
 
-    let stacks = transpose(stacks.into_iter().rev().collect());
+    let arrangement = match arrangement {
+        Some(arrangement) => arrangement,
+        None if stacks.is_empty() => Vec::new(),
+        None => transpose(stacks.into_iter().rev().collect()),
+    };
 
-    Schedule {
-        arrangement: stacks,
+    Ok(Schedule {
+        arrangement,
         instructions,
+    })
+}
+
+fn pop_crate(stack: &mut Vec<char>, idx: usize) -> Result<char, AocError> {
+    stack.pop().ok_or_else(|| {
+        AocError::no_solution(Day::new(5), format!("stack {} ran out of crates", idx + 1))
+    })
+}
+
+fn top_row(arrangement: Vec<Vec<char>>) -> Result<String, AocError> {
+    let mut top_row = String::new();
+    for (idx, stack) in arrangement.into_iter().enumerate() {
+        let top = stack.last().ok_or_else(|| {
+            AocError::no_solution(Day::new(5), format!("stack {} ends up empty", idx + 1))
+        })?;
+        top_row.push(*top);
     }
+    Ok(top_row)
 }
 
-fn solve_part_one() -> String {
-    let mut schedule = parse_manifest(false);
+/// The top crate of each stack when the crane moves one crate at a time.
+///
+/// # Errors
+///
+/// Returns an error if a move takes a crate from an empty stack.
+pub fn solve_part_one(schedule: &Schedule) -> Result<String, AocError> {
+    let mut arrangement = schedule.arrangement.clone();
 
-    for Instruction {
+    for &Instruction {
         source_stack: source,
         target_stack: target,
         move_amount: amount,
-    } in schedule.instructions
+    } in &schedule.instructions
     {
         for _ in 0..amount {
-            let last = schedule.arrangement[source].pop().expect("Get crate");
-            schedule.arrangement[target].push(last);
+            let last = pop_crate(&mut arrangement[source], source)?;
+            arrangement[target].push(last);
         }
     }
 
-    let mut top_row = String::new();
-    for stack in schedule.arrangement {
-        top_row.push(*stack.last().unwrap());
-    }
-    top_row
+    top_row(arrangement)
 }
 
-fn solve_part_two() -> String {
-    let mut schedule = parse_manifest(false);
+/// The top crate of each stack when the crane moves several crates at once.
+///
+/// # Errors
+///
+/// Returns an error if a move takes a crate from an empty stack.
+pub fn solve_part_two(schedule: &Schedule) -> Result<String, AocError> {
+    let mut arrangement = schedule.arrangement.clone();
 
-    for Instruction {
+    for &Instruction {
         source_stack: source,
         target_stack: target,
         move_amount: amount,
-    } in schedule.instructions
+    } in &schedule.instructions
     {
         let mut queue = VecDeque::new();
 
         for _ in 0..amount {
-            let last = schedule.arrangement[source].pop().expect("Get crate");
+            let last = pop_crate(&mut arrangement[source], source)?;
             queue.push_front(last);
         }
 
-        schedule.arrangement[target].extend(&queue);
+        arrangement[target].extend(&queue);
     }
 
-    let mut top_row = String::new();
-    for stack in schedule.arrangement {
-        top_row.push(*stack.last().unwrap());
-    }
-    top_row
+    top_row(arrangement)
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::VecDeque;
-
-    use crate::d05::{parse_manifest, Instruction};
-
-    #[test]
-    fn solve_sample_one() {
-        let mut schedule = parse_manifest(true);
-
-        for Instruction {
-            source_stack: source,
-            target_stack: target,
-            move_amount: amount,
-        } in schedule.instructions
-        {
-            for _ in 0..amount {
-                let last = schedule.arrangement[source].pop().expect("Get crate");
-                schedule.arrangement[target].push(last);
-            }
-        }
-
-        let mut top_row = String::new();
-        for stack in schedule.arrangement {
-            top_row.push(*stack.last().unwrap());
-        }
-
-        assert_eq!("CMZ", top_row);
-    }
-
-    #[test]
-    fn solve_sample_two() {
-        let mut schedule = parse_manifest(true);
-
-        for Instruction {
-            source_stack: source,
-            target_stack: target,
-            move_amount: amount,
-        } in schedule.instructions
-        {
-            let mut queue = VecDeque::new();
-
-            for _ in 0..amount {
-                let last = schedule.arrangement[source].pop().expect("Get crate");
-                queue.push_front(last);
-            }
-
-            schedule.arrangement[target].extend(&queue);
-        }
-
-        let mut top_row = String::new();
-        for stack in schedule.arrangement {
-            top_row.push(*stack.last().unwrap());
-        }
+    use crate::InputSource;
 
-        assert_eq!("MCD", top_row);
+    sample_tests! {
+        super::Solver;
+        sample: InputSource::Sample => { One: "CMZ", Two: "MCD" },
     }
 }
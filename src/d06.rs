@@ -1,32 +1,60 @@
-use std::io::Read;
+//! Day 6: Tuning Trouble.
 
-use crate::{get_day_input, AOCSolver, Day, Part};
+use crate::{
+    get_day_input, read_to_string, registry::Registry, AOCSolver, Answer, AocError, Day,
+    InputSource,
+};
 
+/// Solves both parts of day 6 through [`AOCSolver`].
 pub struct Solver;
 
 impl AOCSolver for Solver {
-    type Solution = usize;
+    type Input = String;
+    type Params = Params;
 
-    fn solve(part: Part) -> Self::Solution {
-        if let Part::One = part {
-            solve_part_one()
-        } else {
-            solve_part_two()
-        }
+    fn parse(source: &InputSource) -> Result<Self::Input, AocError> {
+        load_message(source)
+    }
+
+    fn part_one(input: &Self::Input, params: &Self::Params) -> Result<Answer, AocError> {
+        solve_part_one(input, params).map(Answer::from)
+    }
+
+    fn part_two(input: &Self::Input, params: &Self::Params) -> Result<Answer, AocError> {
+        solve_part_two(input, params).map(Answer::from)
     }
 }
 
-fn load_message(load_sample: bool) -> String {
-    let mut file = get_day_input(Day::new(6), load_sample);
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add(Day::new(6), Solver);
+}
 
-    let mut res = String::new();
+params! {
+    /// Tunable values for day 6.
+    pub struct Params(6) {
+        /// How many distinct characters make a start-of-packet marker.
+        packet_marker: usize = 4,
+        /// How many distinct characters make a start-of-message marker.
+        message_marker: usize = 14,
+    }
+}
 
-    file.read_to_string(&mut res).expect("Reading file");
+/// Reads the datastream buffer.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read.
+pub fn load_message(source: &InputSource) -> Result<String, AocError> {
+    let day = Day::new(6);
+    let reader = get_day_input(day, source)?;
 
-    res
+    read_to_string(day, reader)
 }
 
-fn find_magic_number(message: String, magic_length: usize) -> Option<usize> {
+/// The number of characters read before the first `magic_length` distinct characters in a
+/// row, or `None` if there are none.
+#[must_use]
+pub fn find_magic_number(message: &str, magic_length: usize) -> Option<usize> {
     let mut window = String::new();
     for (idx, char) in message.chars().enumerate() {
         if window.contains(char) {
@@ -43,138 +71,42 @@ fn find_magic_number(message: String, magic_length: usize) -> Option<usize> {
     None
 }
 
-fn solve_part_one() -> usize {
-    let message = load_message(false);
-
-    if let Some(value) = find_magic_number(message, 4) {
-        value
-    } else {
-        panic!("Oh dear we didn't find any sequence");
-    }
+fn no_marker(magic_length: usize) -> AocError {
+    AocError::no_solution(
+        Day::new(6),
+        format!("no sequence of {magic_length} distinct characters in the message"),
+    )
 }
 
-fn solve_part_two() -> usize {
-    let message = load_message(false);
+/// The position of the start-of-packet marker.
+///
+/// # Errors
+///
+/// Returns an error if the message has no marker.
+pub fn solve_part_one(message: &str, params: &Params) -> Result<usize, AocError> {
+    find_magic_number(message, params.packet_marker).ok_or_else(|| no_marker(params.packet_marker))
+}
 
-    if let Some(value) = find_magic_number(message, 14) {
-        value
-    } else {
-        panic!("Oh dear we didn't find any sequence");
-    }
+/// The position of the start-of-message marker.
+///
+/// # Errors
+///
+/// Returns an error if the message has no marker.
+pub fn solve_part_two(message: &str, params: &Params) -> Result<usize, AocError> {
+    find_magic_number(message, params.message_marker)
+        .ok_or_else(|| no_marker(params.message_marker))
 }
+
 #[cfg(test)]
 mod tests {
-    use crate::d06::load_message;
-
-    use super::find_magic_number;
-
-    #[test]
-    fn solve_sample_one_by_l4() {
-        let message = load_message(true);
-
-        if let Some(value) = find_magic_number(message, 4) {
-            assert_eq!(7, value);
-        } else {
-            panic!("Oh dear we didn't find any sequence");
-        }
-    }
-
-    #[test]
-    fn solve_sample_one_by_l14() {
-        let message = load_message(true);
-
-        if let Some(value) = find_magic_number(message, 14) {
-            assert_eq!(19, value);
-        } else {
-            panic!("Oh dear we didn't find any sequence");
-        }
-    }
-
-    #[test]
-    fn solve_sample_two_by_l4() {
-        let message = "bvwbjplbgvbhsrlpgdmjqwftvncz".to_string();
-
-        if let Some(value) = find_magic_number(message, 4) {
-            assert_eq!(5, value);
-        } else {
-            panic!("Oh dear we didn't find any sequence");
-        }
-    }
-
-    #[test]
-    fn solve_sample_two_by_l14() {
-        let message = "bvwbjplbgvbhsrlpgdmjqwftvncz".to_string();
-
-        if let Some(value) = find_magic_number(message, 14) {
-            assert_eq!(23, value);
-        } else {
-            panic!("Oh dear we didn't find any sequence");
-        }
-    }
-
-    #[test]
-    fn solve_sample_three_by_l4() {
-        let message = "nppdvjthqldpwncqszvftbrmjlhg".to_string();
-
-        if let Some(value) = find_magic_number(message, 4) {
-            assert_eq!(6, value);
-        } else {
-            panic!("Oh dear we didn't find any sequence");
-        }
-    }
-
-    #[test]
-    fn solve_sample_three_by_l14() {
-        let message = "nppdvjthqldpwncqszvftbrmjlhg".to_string();
-
-        if let Some(value) = find_magic_number(message, 14) {
-            assert_eq!(23, value);
-        } else {
-            panic!("Oh dear we didn't find any sequence");
-        }
-    }
-
-    #[test]
-    fn solve_sample_four_by_l4() {
-        let message = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg".to_string();
-
-        if let Some(value) = find_magic_number(message, 4) {
-            assert_eq!(10, value);
-        } else {
-            panic!("Oh dear we didn't find any sequence");
-        }
-    }
-
-    #[test]
-    fn solve_sample_four_by_l14() {
-        let message = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg".to_string();
-
-        if let Some(value) = find_magic_number(message, 14) {
-            assert_eq!(29, value);
-        } else {
-            panic!("Oh dear we didn't find any sequence");
-        }
-    }
-
-    #[test]
-    fn solve_sample_five_by_l4() {
-        let message = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw".to_string();
-
-        if let Some(value) = find_magic_number(message, 4) {
-            assert_eq!(11, value);
-        } else {
-            panic!("Oh dear we didn't find any sequence");
-        }
-    }
-
-    #[test]
-    fn solve_sample_five_by_l14() {
-        let message = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw".to_string();
-
-        if let Some(value) = find_magic_number(message, 14) {
-            assert_eq!(26, value);
-        } else {
-            panic!("Oh dear we didn't find any sequence");
-        }
+    use crate::InputSource;
+
+    sample_tests! {
+        super::Solver;
+        sample_one: InputSource::Sample => { One: 7, Two: 19 },
+        sample_two: "bvwbjplbgvbhsrlpgdmjqwftvncz" => { One: 5, Two: 23 },
+        sample_three: "nppdvjthqldpwncqszvftbrmjlhg" => { One: 6, Two: 23 },
+        sample_four: "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg" => { One: 10, Two: 29 },
+        sample_five: "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw" => { One: 11, Two: 26 },
     }
 }
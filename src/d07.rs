@@ -1,20 +1,47 @@
-use std::{collections::HashMap, io::BufRead};
+//! Day 7: No Space Left On Device.
+
+use std::collections::HashMap;
 
 use petgraph::{prelude::DiGraph, stable_graph::NodeIndex, visit::Dfs, Graph};
 
-use crate::{get_day_input, AOCSolver, Day, Part};
+use crate::{
+    error::Line, get_day_input, read_lines, registry::Registry, AOCSolver, Answer, AocError, Day,
+    InputSource,
+};
 
+/// Solves both parts of day 7 through [`AOCSolver`].
 pub struct Solver;
 
 impl AOCSolver for Solver {
-    type Solution = u64;
+    type Input = Filesystem;
+    type Params = Params;
 
-    fn solve(part: Part) -> Self::Solution {
-        if let Part::One = part {
-            solve_part_one()
-        } else {
-            solve_part_two()
-        }
+    fn parse(source: &InputSource) -> Result<Self::Input, AocError> {
+        parse_filesystem(source)
+    }
+
+    fn part_one(input: &Self::Input, params: &Self::Params) -> Result<Answer, AocError> {
+        solve_part_one(input, params).map(Answer::from)
+    }
+
+    fn part_two(input: &Self::Input, params: &Self::Params) -> Result<Answer, AocError> {
+        solve_part_two(input, params).map(Answer::from)
+    }
+}
+
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add(Day::new(7), Solver);
+}
+
+params! {
+    /// Tunable values for day 7.
+    pub struct Params(7) {
+        /// The total size of the disk.
+        disk_size: u64 = 70_000_000,
+        /// How much unused space the update needs.
+        needed_space: u64 = 30_000_000,
+        /// The largest directory part one counts.
+        small_dir_limit: u64 = 100_000,
     }
 }
 
@@ -36,37 +63,79 @@ enum Statement {
     Handle(Handle),
 }
 
-fn parse_input(data: &[String]) -> Vec<Statement> {
+fn parse_input(day: Day, data: &[String]) -> Result<Vec<Statement>, AocError> {
     let mut statements = Vec::new();
 
-    for line in data {
-        let parts = line.split_ascii_whitespace().collect::<Vec<_>>();
+    for (idx, text) in data.iter().enumerate() {
+        let line = Line::new(day, idx, text);
 
-        if parts[0] == "$" {
-            if parts[1] == "ls" {
+        let first = line.field(0, "a command or a directory listing")?;
+
+        if first == "$" {
+            let command = line.field(1, "a command")?;
+            if command == "ls" {
                 statements.push(Statement::Command(Command::List));
+            } else if command == "cd" {
+                let dir = line.field(2, "a directory name")?;
+                statements.push(Statement::Command(Command::Change(dir.to_owned())));
             } else {
-                statements.push(Statement::Command(Command::Change(parts[2].to_owned())));
+                return Err(line.error(command, "expected `cd` or `ls`"));
             }
-        } else if parts[0] == "dir" {
-            statements.push(Statement::Handle(Handle::Directory(parts[1].to_owned())));
+        } else if first == "dir" {
+            let name = line.field(1, "a directory name")?;
+            statements.push(Statement::Handle(Handle::Directory(name.to_owned())));
         } else {
+            let name = line.field(1, "a file name")?;
             statements.push(Statement::Handle(Handle::File(
-                parts[1].to_owned(),
-                parts[0].parse().unwrap(),
+                name.to_owned(),
+                line.parse(first)?,
             )));
         }
     }
 
-    statements
+    Ok(statements)
 }
 
-fn parse_filesystem(load_sample: bool) -> DiGraph<String, u64> {
-    let file = get_day_input(Day::new(7), load_sample);
+/// The directory tree rebuilt from the terminal output, with the size of every directory.
+#[derive(Debug, Clone)]
+pub struct Filesystem {
+    graph: DiGraph<String, u64>,
+}
 
-    let reader = std::io::BufReader::new(file);
+impl Filesystem {
+    /// The total size of every directory, including `/`, smallest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the terminal output never visits `/`.
+    pub fn directory_sizes(&self) -> Result<Vec<u64>, AocError> {
+        let root = find_root(&self.graph)?;
+
+        let mut sizes = find_directory_sizes(&self.graph, root)
+            .into_values()
+            .collect::<Vec<_>>();
+        sizes.sort_unstable();
+
+        Ok(sizes)
+    }
+}
 
-    let data = reader.lines().flatten().collect::<Vec<String>>();
+/// Replays the `cd` and `ls` commands in the terminal output.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read or a line is malformed.
+pub fn parse_filesystem(source: &InputSource) -> Result<Filesystem, AocError> {
+    read_terminal_output(source).map(|graph| Filesystem {
+        graph: update_filesystem(graph),
+    })
+}
+
+fn read_terminal_output(source: &InputSource) -> Result<DiGraph<String, u64>, AocError> {
+    let day = Day::new(7);
+    let file = get_day_input(day, source)?;
+
+    let data = read_lines(day, file).collect::<Result<Vec<String>, _>>()?;
 
     let mut filesystem = DiGraph::new();
 
@@ -74,9 +143,12 @@ fn parse_filesystem(load_sample: bool) -> DiGraph<String, u64> {
 
     let mut current_dir = None;
 
-    let statements = parse_input(&data);
+    let statements = parse_input(day, &data)?;
+
+    for (idx, statement) in statements.into_iter().enumerate() {
+        let line = Line::new(day, idx, &data[idx]);
+        let outside_root = || line.error(line.text(), "expected to be inside `/`");
 
-    for statement in statements {
         match statement {
             Statement::Command(c) => match c {
                 Command::List => continue,
@@ -88,41 +160,47 @@ fn parse_filesystem(load_sample: bool) -> DiGraph<String, u64> {
                         let root_node = filesystem.add_node(dir);
                         dir_stack.push(root_node);
                         Some(root_node)
-                    } else if let Some(neighbor) = filesystem
-                        .neighbors(current_dir.unwrap())
-                        .find(|n| filesystem[*n] == *dir)
-                    {
+                    } else {
+                        let current = current_dir.ok_or_else(outside_root)?;
+                        let neighbor = filesystem
+                            .neighbors(current)
+                            .find(|n| filesystem[*n] == *dir)
+                            .ok_or_else(|| {
+                                line.error(
+                                    line.text(),
+                                    format!("{dir} is not reachable from {}", filesystem[current]),
+                                )
+                            })?;
                         dir_stack.push(neighbor);
                         Some(neighbor)
-                    } else {
-                        panic!(
-                            "{} is not reachable from {}",
-                            dir,
-                            filesystem[current_dir.unwrap()]
-                        );
                     };
                 }
             },
-            Statement::Handle(h) => match h {
-                Handle::File(name, size) => {
-                    let new_node = filesystem.add_node(name);
-                    filesystem.add_edge(current_dir.unwrap(), new_node, size);
+            Statement::Handle(h) => {
+                let current = current_dir.ok_or_else(outside_root)?;
+                match h {
+                    Handle::File(name, size) => {
+                        let new_node = filesystem.add_node(name);
+                        filesystem.add_edge(current, new_node, size);
+                    }
+                    Handle::Directory(name) => {
+                        let new_node = filesystem.add_node(name);
+                        filesystem.add_edge(current, new_node, 0);
+                    }
                 }
-                Handle::Directory(name) => {
-                    let new_node = filesystem.add_node(name);
-                    filesystem.add_edge(current_dir.unwrap(), new_node, 0);
-                }
-            },
+            }
         }
     }
 
-    filesystem
+    Ok(filesystem)
 }
 
 fn update_filesystem(filesystem: DiGraph<String, u64>) -> DiGraph<String, u64> {
     let mut filesystem = filesystem;
 
-    let root_node = filesystem.node_indices().next().unwrap();
+    let Some(root_node) = filesystem.node_indices().next() else {
+        return filesystem;
+    };
 
     let mut dfs = Dfs::new(&filesystem, root_node);
 
@@ -177,76 +255,69 @@ fn find_directory_sizes(
     dirs
 }
 
-fn find_min_dir_size(dirs: HashMap<NodeIndex, u64>, root: NodeIndex) -> u64 {
-    const MAX_SPACE: u64 = 70_000_000;
-    const MIN_SPACE: u64 = 30_000_000;
-
-    let filesystem_amount_left = MAX_SPACE - dirs[&root];
+fn find_min_dir_size(
+    dirs: HashMap<NodeIndex, u64>,
+    root: NodeIndex,
+    params: &Params,
+) -> Option<u64> {
+    let filesystem_amount_left = params.disk_size.checked_sub(*dirs.get(&root)?)?;
     let mut dirs_as_vec = dirs.into_iter().collect::<Vec<(_, _)>>();
-    dirs_as_vec.sort_by(|(_, s1), (_, s2)| s1.cmp(s2));
+    dirs_as_vec.sort_by_key(|(_, size)| *size);
 
-    let min_space = dirs_as_vec.into_iter().find_map(|(_, size)| {
-        if filesystem_amount_left + size >= MIN_SPACE {
+    dirs_as_vec.into_iter().find_map(|(_, size)| {
+        if filesystem_amount_left + size >= params.needed_space {
             Some(size)
         } else {
             None
         }
-    });
-
-    min_space.unwrap()
+    })
 }
 
-fn solve_part_one() -> u64 {
-    let filesystem = parse_filesystem(false);
-    let filesystem = update_filesystem(filesystem);
-
-    let root = filesystem.node_indices().next().unwrap();
-
-    let dirs = find_directory_sizes(&filesystem, root);
-
-    dirs.values().filter(|w| **w <= 100_000).sum::<u64>()
+fn find_root(filesystem: &DiGraph<String, u64>) -> Result<NodeIndex, AocError> {
+    filesystem
+        .node_indices()
+        .next()
+        .ok_or_else(|| AocError::no_solution(Day::new(7), "the terminal output never visits `/`"))
 }
 
-fn solve_part_two() -> u64 {
-    let filesystem = parse_filesystem(false);
-    let filesystem = update_filesystem(filesystem);
+/// The summed size of every directory of at most [`Params::small_dir_limit`].
+///
+/// # Errors
+///
+/// Returns an error if the terminal output never visits `/`.
+pub fn solve_part_one(filesystem: &Filesystem, params: &Params) -> Result<u64, AocError> {
+    let filesystem = &filesystem.graph;
+    let root = find_root(filesystem)?;
+
+    let dirs = find_directory_sizes(filesystem, root);
+
+    Ok(dirs
+        .values()
+        .filter(|w| **w <= params.small_dir_limit)
+        .sum::<u64>())
+}
 
-    let root = filesystem.node_indices().next().unwrap();
+/// The size of the smallest directory that frees up enough space for the update.
+///
+/// # Errors
+///
+/// Returns an error if the terminal output never visits `/` or no directory is big enough.
+pub fn solve_part_two(filesystem: &Filesystem, params: &Params) -> Result<u64, AocError> {
+    let filesystem = &filesystem.graph;
+    let root = find_root(filesystem)?;
 
-    let dirs = find_directory_sizes(&filesystem, root);
+    let dirs = find_directory_sizes(filesystem, root);
 
-    find_min_dir_size(dirs, root)
+    find_min_dir_size(dirs, root, params)
+        .ok_or_else(|| AocError::no_solution(Day::new(7), "no directory frees up enough space"))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{find_directory_sizes, find_min_dir_size, parse_filesystem, update_filesystem};
-
-    #[test]
-    fn solve_sample_one() {
-        let filesystem = parse_filesystem(true);
-        let filesystem = update_filesystem(filesystem);
-
-        let root = filesystem.node_indices().next().unwrap();
-
-        let dirs = find_directory_sizes(&filesystem, root);
-
-        let total_size = dirs.values().filter(|w| **w <= 100_000).sum::<u64>();
-
-        assert_eq!(total_size, 95437);
-    }
-
-    #[test]
-    fn solve_sample_two() {
-        let filesystem = parse_filesystem(true);
-        let filesystem = update_filesystem(filesystem);
-
-        let root = filesystem.node_indices().next().unwrap();
-
-        let dirs = find_directory_sizes(&filesystem, root);
-
-        let min_size = find_min_dir_size(dirs, root);
+    use crate::InputSource;
 
-        assert_eq!(min_size, 24_933_642);
+    sample_tests! {
+        super::Solver;
+        sample: InputSource::Sample => { One: 95437, Two: 24_933_642 },
     }
 }
@@ -1,30 +1,49 @@
-use std::{
-    io::BufRead,
-    iter::{IntoIterator, Iterator},
-};
+//! Day 8: Treetop Tree House.
+
+use std::iter::{IntoIterator, Iterator};
 
-use crate::{get_day_input, AOCSolver, Day, Part};
+use crate::{
+    error::Line, get_day_input, read_lines, registry::Registry, AOCSolver, Answer, AocError, Day,
+    InputSource,
+};
 
+/// Solves both parts of day 8 through [`AOCSolver`].
 pub struct Solver;
 
 impl AOCSolver for Solver {
-    type Solution = u64;
+    type Input = Forest;
+    type Params = ();
 
-    fn solve(part: Part) -> Self::Solution {
-        if let Part::One = part {
-            solve_part_one()
-        } else {
-            solve_part_two()
-        }
+    fn parse(source: &InputSource) -> Result<Self::Input, AocError> {
+        parse_forest(source)
+    }
+
+    fn part_one(input: &Self::Input, _: &()) -> Result<Answer, AocError> {
+        solve_part_one(input).map(Answer::from)
+    }
+
+    fn part_two(input: &Self::Input, _: &()) -> Result<Answer, AocError> {
+        solve_part_two(input).map(Answer::from)
     }
 }
 
-struct Forest {
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add(Day::new(8), Solver);
+}
+
+/// A grid of tree heights.
+pub struct Forest {
     trees: Vec<Vec<u64>>,
     flipped_trees: Vec<Vec<u64>>,
 }
 
 impl Forest {
+    /// A forest of `trees`, given row by row, with every row as long as the first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `trees` is empty.
+    #[must_use]
     pub fn new(trees: Vec<Vec<u64>>) -> Self {
         Self {
             trees: trees.clone(),
@@ -32,18 +51,35 @@ impl Forest {
         }
     }
 
+    /// The height of the tree at `tree_row` and `tree_col`, counted from the top left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is outside the forest.
+    #[must_use]
     pub fn tree(&self, tree_row: usize, tree_col: usize) -> u64 {
         self.trees[tree_row][tree_col]
     }
 
+    /// How many rows of trees the forest has.
+    #[must_use]
     pub fn num_rows(&self) -> usize {
         self.trees.len()
     }
 
+    /// How many trees each row has.
+    #[must_use]
     pub fn num_cols(&self) -> usize {
         self.flipped_trees.len()
     }
 
+    /// Whether the tree at `tree_row` and `tree_col` can be seen from outside the forest,
+    /// looking along its row or column past only shorter trees.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is outside the forest.
+    #[must_use]
     pub fn is_visible(&self, tree_row: usize, tree_col: usize) -> bool {
         if tree_row == 0
             || tree_row == self.num_rows() - 1
@@ -69,6 +105,13 @@ impl Forest {
             || right_row.all(|x| *x < tree)
     }
 
+    /// The product of how many trees can be seen from the tree at `tree_row` and `tree_col`
+    /// in each direction, up to and including the first one at least as tall.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is outside the forest.
+    #[must_use]
     pub fn scenic_score(&self, tree_row: usize, tree_col: usize) -> u64 {
         let tree = self.tree(tree_row, tree_col);
 
@@ -132,30 +175,53 @@ impl Forest {
     }
 }
 
-fn parse_forest(load_sample: bool) -> Forest {
-    let file = get_day_input(Day::new(8), load_sample);
+/// Reads one row of single-digit tree heights per line.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read or a line is malformed.
+pub fn parse_forest(source: &InputSource) -> Result<Forest, AocError> {
+    let day = Day::new(8);
+    let file = get_day_input(day, source)?;
 
-    let reader = std::io::BufReader::new(file);
+    let mut trees: Vec<Vec<u64>> = Vec::new();
 
-    let mut trees = Vec::new();
+    for (idx, text) in read_lines(day, file).enumerate() {
+        let text = text?;
+        let line = Line::new(day, idx, &text);
 
-    for line in reader.lines().flatten() {
         let mut row = Vec::new();
 
-        for char in line.chars() {
-            let value = u64::from(char.to_digit(10).unwrap());
-            row.push(value);
+        for (col, char) in text.char_indices() {
+            let value = char
+                .to_digit(10)
+                .ok_or_else(|| line.error(&text[col..col + char.len_utf8()], "expected a digit"))?;
+            row.push(u64::from(value));
+        }
+
+        if let Some(first_row) = trees.first() {
+            if first_row.len() != row.len() {
+                return Err(line.error(
+                    &text,
+                    format!("expected a row of {} trees", first_row.len()),
+                ));
+            }
+        } else if row.is_empty() {
+            return Err(line.missing("a row of trees"));
         }
 
         trees.push(row);
     }
 
-    Forest::new(trees)
-}
+    if trees.is_empty() {
+        return Err(AocError::no_solution(day, "the forest has no trees"));
+    }
 
-fn solve_part_one() -> u64 {
-    let forest = parse_forest(false);
+    Ok(Forest::new(trees))
+}
 
+/// How many trees are visible from outside the grid.
+pub fn solve_part_one(forest: &Forest) -> Result<u64, AocError> {
     let num_rows = forest.num_rows();
     let num_cols = forest.num_cols();
 
@@ -169,12 +235,11 @@ fn solve_part_one() -> u64 {
         }
     }
 
-    num_trees_visible
+    Ok(num_trees_visible)
 }
 
-fn solve_part_two() -> u64 {
-    let forest = parse_forest(false);
-
+/// The highest scenic score of any tree.
+pub fn solve_part_two(forest: &Forest) -> Result<u64, AocError> {
     let num_rows = forest.num_rows();
     let num_cols = forest.num_cols();
 
@@ -189,51 +254,15 @@ fn solve_part_two() -> u64 {
         }
     }
 
-    max_scenic_score
+    Ok(max_scenic_score)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::d08::parse_forest;
-
-    #[test]
-    fn solve_sample_one() {
-        let forest = parse_forest(true);
-
-        let num_rows = forest.num_rows();
-        let num_cols = forest.num_cols();
-
-        let mut num_trees_visible = 0;
-
-        for row in 0..num_rows {
-            for col in 0..num_cols {
-                if forest.is_visible(row, col) {
-                    num_trees_visible += 1;
-                }
-            }
-        }
-
-        assert_eq!(num_trees_visible, 21);
-    }
-
-    #[test]
-    fn solve_sample_two() {
-        let forest = parse_forest(true);
-
-        let num_rows = forest.num_rows();
-        let num_cols = forest.num_cols();
-
-        let mut max_scenic_score = 0;
-
-        for row in 0..num_rows {
-            for col in 0..num_cols {
-                let scenic_score = forest.scenic_score(row, col);
-                if scenic_score >= max_scenic_score {
-                    max_scenic_score = scenic_score;
-                }
-            }
-        }
+    use crate::InputSource;
 
-        assert_eq!(max_scenic_score, 8);
+    sample_tests! {
+        super::Solver;
+        sample: InputSource::Sample => { One: 21, Two: 8 },
     }
 }
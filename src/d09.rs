@@ -1,22 +1,48 @@
-use std::{collections::HashSet, io::BufRead};
+//! Day 9: Rope Bridge.
 
-use crate::{get_day_input, AOCSolver, Day, Part};
+use std::collections::HashSet;
 
+use crate::{
+    error::Line, get_day_input, read_lines, registry::Registry, AOCSolver, Answer, AocError, Day,
+    InputSource,
+};
+
+/// Solves both parts of day 9 through [`AOCSolver`].
 pub struct Solver;
 
 impl AOCSolver for Solver {
-    type Solution = usize;
+    type Input = Vec<Move>;
+    type Params = Params;
 
-    fn solve(part: Part) -> Self::Solution {
-        if let Part::One = part {
-            solve_part_one()
-        } else {
-            solve_part_two()
-        }
+    fn parse(source: &InputSource) -> Result<Self::Input, AocError> {
+        parse_movements(source)
+    }
+
+    fn part_one(input: &Self::Input, params: &Self::Params) -> Result<Answer, AocError> {
+        solve_part_one(input, params).map(Answer::from)
+    }
+
+    fn part_two(input: &Self::Input, params: &Self::Params) -> Result<Answer, AocError> {
+        solve_part_two(input, params).map(Answer::from)
+    }
+}
+
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add(Day::new(9), Solver);
+}
+
+params! {
+    /// Tunable values for day 9.
+    pub struct Params(9) {
+        /// How many knots the rope has in part one.
+        short_rope: usize = 2,
+        /// How many knots the rope has in part two.
+        long_rope: usize = 10,
     }
 }
 
-#[derive(Clone, Copy)]
+/// A direction the head of the rope can move in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
     Up,
     Down,
@@ -24,10 +50,11 @@ pub enum Direction {
     Right,
 }
 
-#[derive(Clone, Copy)]
+/// Moves the head of the rope `steps` times in `direction`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Move {
-    direction: Direction,
-    steps: u64,
+    pub direction: Direction,
+    pub steps: u64,
 }
 
 #[derive(Default, Clone, Copy, PartialEq, Eq, Hash)]
@@ -128,12 +155,18 @@ impl Rope {
     }
 }
 
+/// A rope with any number of knots and the positions its tail has visited.
 pub struct State {
     rope: Rope,
     visited_positions: HashSet<Position>,
 }
 
 impl State {
+    /// A rope of `knots` knots, all at the origin.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are fewer than two knots.
     #[must_use]
     pub fn new(knots: usize) -> Self {
         assert!(knots >= 2, "There must be at least two knots in the rope");
@@ -146,6 +179,7 @@ impl State {
         }
     }
 
+    /// Moves the head and lets the rest of the rope follow, one step at a time.
     pub fn update(&mut self, r#move: Move) {
         for _ in 0..r#move.steps {
             self.rope.update(r#move.direction);
@@ -154,137 +188,112 @@ impl State {
         }
     }
 
+    /// How many distinct positions the tail has visited, including the origin.
     #[must_use]
     pub fn num_visited_positions(&self) -> usize {
         self.visited_positions.len()
     }
 }
 
-fn parse_movements(load_sample: bool) -> Vec<Move> {
-    let file = get_day_input(Day::new(9), load_sample);
-
-    let reader = std::io::BufReader::new(file);
+/// Reads one [`Move`] per line.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read or a line is malformed.
+pub fn parse_movements(source: &InputSource) -> Result<Vec<Move>, AocError> {
+    let day = Day::new(9);
+    let file = get_day_input(day, source)?;
 
     let mut moves = Vec::new();
 
-    for line in reader.lines().flatten() {
-        let parts = line.split_ascii_whitespace().collect::<Vec<_>>();
+    for (idx, text) in read_lines(day, file).enumerate() {
+        let text = text?;
+        let line = Line::new(day, idx, &text);
+
+        let direction = line.field(0, "a direction")?;
 
         let motion = Move {
-            direction: match parts.first() {
-                Some(&"U") => Direction::Up,
-                Some(&"D") => Direction::Down,
-                Some(&"L") => Direction::Left,
-                Some(&"R") => Direction::Right,
-                _ => unreachable!(),
+            direction: match direction {
+                "U" => Direction::Up,
+                "D" => Direction::Down,
+                "L" => Direction::Left,
+                "R" => Direction::Right,
+                _ => return Err(line.error(direction, "expected a direction (U/D/L/R)")),
             },
-            steps: parts[1].parse().unwrap(),
+            steps: line.parse(line.field(1, "a step count")?)?,
         };
         moves.push(motion);
     }
 
-    moves
+    Ok(moves)
 }
 
-fn solve_part_one() -> usize {
-    let instructions = parse_movements(false);
+fn count_tail_positions(instructions: &[Move], knots: usize) -> Result<usize, AocError> {
+    if knots < 2 {
+        return Err(AocError::no_solution(
+            Day::new(9),
+            format!("a rope needs at least two knots. Got {knots} instead."),
+        ));
+    }
 
-    let mut state = State::new(2);
+    let mut state = State::new(knots);
 
-    for r#move in &instructions {
+    for r#move in instructions {
         state.update(*r#move);
     }
 
-    state.num_visited_positions()
+    Ok(state.num_visited_positions())
 }
 
-fn solve_part_two() -> usize {
-    let instructions = parse_movements(false);
-
-    let mut state = State::new(10);
-
-    for r#move in &instructions {
-        state.update(*r#move);
-    }
+/// How many positions the tail of a [`Params::short_rope`] rope visits.
+///
+/// # Errors
+///
+/// Returns an error if the rope has fewer than two knots.
+pub fn solve_part_one(instructions: &[Move], params: &Params) -> Result<usize, AocError> {
+    count_tail_positions(instructions, params.short_rope)
+}
 
-    state.num_visited_positions()
+/// How many positions the tail of a [`Params::long_rope`] rope visits.
+///
+/// # Errors
+///
+/// Returns an error if the rope has fewer than two knots.
+pub fn solve_part_two(instructions: &[Move], params: &Params) -> Result<usize, AocError> {
+    count_tail_positions(instructions, params.long_rope)
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::d09::{Direction, Move};
-
-    use super::{parse_movements, State};
-
-    #[test]
-    fn solve_sample_one() {
-        let instructions = parse_movements(true);
+    use crate::{AOCSolver, AocError, InputSource};
 
-        let mut state = State::new(2);
+    use super::{Params, Solver};
 
-        for r#move in &instructions {
-            state.update(*r#move);
-        }
-
-        assert_eq!(state.num_visited_positions(), 13);
+    sample_tests! {
+        Solver;
+        sample: InputSource::Sample => { One: 13, Two: 1 },
+        larger_sample: "R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20\n" => { Two: 36 },
     }
 
     #[test]
-    fn solve_sample_two() {
-        let instructions = parse_movements(true);
-
-        let mut state = State::new(10);
-
-        for r#move in &instructions {
-            state.update(*r#move);
-        }
-
-        assert_eq!(state.num_visited_positions(), 1);
-    }
-
-    #[test]
-    fn solve_sample_three() {
-        let instructions = vec![
-            Move {
-                direction: Direction::Right,
-                steps: 5,
-            },
-            Move {
-                direction: Direction::Up,
-                steps: 8,
-            },
-            Move {
-                direction: Direction::Left,
-                steps: 8,
-            },
-            Move {
-                direction: Direction::Down,
-                steps: 3,
-            },
-            Move {
-                direction: Direction::Right,
-                steps: 17,
-            },
-            Move {
-                direction: Direction::Down,
-                steps: 10,
-            },
-            Move {
-                direction: Direction::Left,
-                steps: 25,
-            },
-            Move {
-                direction: Direction::Up,
-                steps: 20,
-            },
-        ];
-
-        let mut state = State::new(10);
-
-        for r#move in &instructions {
-            state.update(*r#move);
-        }
+    fn ropes_of_any_length() {
+        let moves = Solver::parse(&InputSource::Sample).unwrap();
+        let params = Params {
+            short_rope: 10,
+            long_rope: 2,
+        };
 
-        assert_eq!(state.num_visited_positions(), 36);
+        assert_eq!(1, super::solve_part_one(&moves, &params).unwrap());
+        assert_eq!(13, super::solve_part_two(&moves, &params).unwrap());
+        assert!(matches!(
+            super::solve_part_one(
+                &moves,
+                &Params {
+                    short_rope: 1,
+                    ..params
+                }
+            ),
+            Err(AocError::NoSolution { .. })
+        ));
     }
 }
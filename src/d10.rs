@@ -1,36 +1,72 @@
-use std::io::BufRead;
+//! Day 10: Cathode-Ray Tube.
 
-use crate::{get_day_input, AOCSolver, Day, Part};
-
-const SCREEN_WIDTH: usize = 40;
+use crate::{
+    error::Line, get_day_input, read_lines, registry::Registry, AOCSolver, Answer, AocError, Day,
+    InputSource,
+};
 
+/// Solves both parts of day 10 through [`AOCSolver`].
 pub struct Solver;
 
 impl AOCSolver for Solver {
-    type Solution = String;
+    type Input = Vec<Instruction>;
+    type Params = Params;
 
-    fn solve(part: Part) -> Self::Solution {
-        if let Part::One = part {
-            solve_part_one()
-        } else {
-            solve_part_two()
-        }
+    fn parse(source: &InputSource) -> Result<Self::Input, AocError> {
+        parse_instructions(source)
+    }
+
+    fn part_one(input: &Self::Input, params: &Self::Params) -> Result<Answer, AocError> {
+        solve_part_one(input, params).map(Answer::from)
+    }
+
+    fn part_two(input: &Self::Input, params: &Self::Params) -> Result<Answer, AocError> {
+        solve_part_two(input, params).map(Answer::Multiline)
     }
 }
 
-type Instruction = Option<i64>;
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add(Day::new(10), Solver);
+}
+
+params! {
+    /// Tunable values for day 10.
+    pub struct Params(10) {
+        /// How many pixels the CRT draws per row.
+        screen_width: usize = 40,
+    }
+}
 
+/// The signal strength is sampled during the 20th cycle and every 40 cycles after it, no
+/// matter how wide the screen is.
+const FIRST_SAMPLE: usize = 20;
+const SAMPLE_INTERVAL: usize = 40;
+
+/// `addx` with the amount to add, or `None` for `noop`.
+pub type Instruction = Option<i64>;
+
+/// The handheld device's CPU and the CRT it draws to.
 #[derive(Debug)]
-struct CPU {
+pub struct Cpu {
+    screen_width: usize,
     register: i64,
     clock: usize,
     signal_strength_buffer: Vec<i64>,
     screen_buffer: Vec<char>,
 }
 
-impl CPU {
+impl Default for Cpu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cpu {
+    /// A CPU driving a CRT of the default width.
+    #[must_use]
     pub fn new() -> Self {
         Self {
+            screen_width: Params::default().screen_width,
             register: 1,
             clock: 0,
             signal_strength_buffer: Vec::new(),
@@ -38,6 +74,26 @@ impl CPU {
         }
     }
 
+    /// A CPU driving a CRT `screen_width` pixels wide.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `screen_width` is zero.
+    pub fn with_screen_width(screen_width: usize) -> Result<Self, AocError> {
+        if screen_width == 0 {
+            return Err(AocError::no_solution(
+                Day::new(10),
+                "the screen must be at least one pixel wide",
+            ));
+        }
+
+        Ok(Self {
+            screen_width,
+            ..Self::new()
+        })
+    }
+
+    /// Runs one instruction, drawing a pixel and sampling the signal strength every cycle.
     pub fn execute(&mut self, instruction: Instruction) {
         let (tick_counter, add_amount) = if let Some(x) = instruction {
             (2, x)
@@ -48,7 +104,7 @@ impl CPU {
         for tick in 0..tick_counter {
             self.screen_buffer.push({
                 if ((self.register - 1) as usize..=(self.register + 1) as usize)
-                    .contains(&(self.clock % SCREEN_WIDTH))
+                    .contains(&(self.clock % self.screen_width))
                 {
                     '#'
                 } else {
@@ -56,7 +112,7 @@ impl CPU {
                 }
             });
             self.tick();
-            if (self.clock + 20) % SCREEN_WIDTH == 0 {
+            if self.clock % SAMPLE_INTERVAL == FIRST_SAMPLE {
                 self.signal_strength_buffer
                     .push(self.clock as i64 * self.register);
             }
@@ -67,16 +123,20 @@ impl CPU {
         }
     }
 
+    /// The sum of the signal strengths sampled during the 20th, 60th, 100th, ... cycles.
+    #[must_use]
     pub fn calculate_signal_strength_sum(&self) -> i64 {
         self.signal_strength_buffer.iter().sum()
     }
 
+    /// The pixels drawn so far, one line per row of the screen.
+    #[must_use]
     pub fn display_screen_buffer(&self) -> String {
         let mut screen = String::new();
 
         for (idx, element) in self.screen_buffer.iter().enumerate() {
             screen.push(*element);
-            if (idx + 1) % SCREEN_WIDTH == 0 {
+            if (idx + 1).is_multiple_of(self.screen_width) {
                 screen += "\n";
             }
         }
@@ -89,100 +149,114 @@ impl CPU {
     }
 }
 
-fn parse_instructions(load_sample: bool) -> Vec<Instruction> {
-    let file = get_day_input(Day::new(10), load_sample);
-
-    let reader = std::io::BufReader::new(file);
+/// Reads one [`Instruction`] per line.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read or a line is malformed.
+pub fn parse_instructions(source: &InputSource) -> Result<Vec<Instruction>, AocError> {
+    let day = Day::new(10);
+    let file = get_day_input(day, source)?;
 
     let mut instructions = Vec::new();
 
-    for line in reader.lines().flatten() {
-        if line == "noop" {
-            instructions.push(None)
-        } else {
-            let parts = line.split_ascii_whitespace().collect::<Vec<_>>();
+    for (idx, text) in read_lines(day, file).enumerate() {
+        let text = text?;
+        let line = Line::new(day, idx, &text);
+
+        let opcode = line.field(0, "an instruction")?;
 
-            let add_amount = parts[1].parse().unwrap();
+        if opcode == "noop" {
+            instructions.push(None);
+        } else if opcode == "addx" {
+            let add_amount = line.parse(line.field(1, "an amount to add")?)?;
             instructions.push(Some(add_amount));
+        } else {
+            return Err(line.error(opcode, "expected `noop` or `addx`"));
         }
     }
 
-    instructions
+    Ok(instructions)
 }
 
-fn solve_part_one() -> String {
-    let instructions = parse_instructions(false);
-
-    let mut cpu = CPU::new();
+fn run_program(instructions: &[Instruction], params: &Params) -> Result<Cpu, AocError> {
+    let mut cpu = Cpu::with_screen_width(params.screen_width)?;
 
     for instruction in instructions {
-        cpu.execute(instruction);
+        cpu.execute(*instruction);
     }
 
-    cpu.calculate_signal_strength_sum().to_string()
+    Ok(cpu)
 }
 
-fn solve_part_two() -> String {
-    let instructions = parse_instructions(false);
-
-    let mut cpu = CPU::new();
-
-    for instruction in instructions {
-        cpu.execute(instruction);
-    }
+/// The sum of the six sampled signal strengths.
+///
+/// # Errors
+///
+/// Returns an error if the screen has no width.
+pub fn solve_part_one(instructions: &[Instruction], params: &Params) -> Result<i64, AocError> {
+    run_program(instructions, params).map(|cpu| cpu.calculate_signal_strength_sum())
+}
 
-    cpu.display_screen_buffer()
+/// The image drawn on the screen.
+///
+/// # Errors
+///
+/// Returns an error if the screen has no width.
+pub fn solve_part_two(instructions: &[Instruction], params: &Params) -> Result<String, AocError> {
+    run_program(instructions, params).map(|cpu| cpu.display_screen_buffer())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_instructions, CPU};
+    use crate::{AOCSolver, Answer, InputSource};
 
-    #[test]
-    fn solve_sample_zero() {
-        let instructions = vec![None, Some(3), Some(-5)];
+    use super::{Cpu, Params, Solver};
 
-        let mut cpu = CPU::new();
+    const SAMPLE_SCREEN: &str = r"##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....
+";
 
-        for instruction in instructions {
-            cpu.execute(instruction);
-            println!("{:?}", cpu);
-        }
+    sample_tests! {
+        Solver;
+        sample: InputSource::Sample => { One: 13140, Two: SAMPLE_SCREEN },
     }
 
     #[test]
-    fn solve_sample_one() {
-        let instructions = parse_instructions(true);
+    fn solve_sample_zero() {
+        let instructions = Solver::parse(&InputSource::from("noop\naddx 3\naddx -5\n")).unwrap();
 
-        let mut cpu = CPU::new();
+        let mut cpu = Cpu::new();
 
         for instruction in instructions {
             cpu.execute(instruction);
         }
 
-        assert_eq!(13140, cpu.calculate_signal_strength_sum())
+        assert_eq!(-1, cpu.register);
     }
 
     #[test]
-    fn solve_sample_two() {
-        let instructions = parse_instructions(true);
-
-        let mut cpu = CPU::new();
-
-        for instruction in instructions {
-            cpu.execute(instruction);
-        }
-
-        let display = cpu.display_screen_buffer();
+    fn screen_width_leaves_signal_strength_alone() {
+        let instructions = Solver::parse(&InputSource::Sample).unwrap();
+        let narrow = Params { screen_width: 20 };
+
+        assert_eq!(
+            Answer::Integer(13140),
+            Solver::part_one(&instructions, &narrow).unwrap()
+        );
+    }
 
-        let expected = r"##..##..##..##..##..##..##..##..##..##..
-###...###...###...###...###...###...###.
-####....####....####....####....####....
-#####.....#####.....#####.....#####.....
-######......######......######......####
-#######.......#######.......#######.....
-";
+    #[test]
+    fn reject_an_empty_screen() {
+        let error = Cpu::with_screen_width(0).err().unwrap();
 
-        assert_eq!(expected, display)
+        assert_eq!(
+            "day 10: the screen must be at least one pixel wide",
+            error.to_string()
+        );
     }
 }
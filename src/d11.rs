@@ -1,18 +1,45 @@
-use std::{collections::VecDeque, io::BufRead};
+//! Day 11: Monkey in the Middle.
 
-use crate::{get_day_input, AOCSolver, Day, Part};
+use std::collections::VecDeque;
 
-pub struct D11Solver;
+use crate::{
+    error::Line, get_day_input, read_lines, registry::Registry, AOCSolver, Answer, AocError, Day,
+    InputSource,
+};
 
-impl AOCSolver for D11Solver {
-    type Solution = u64;
+/// Solves both parts of day 11 through [`AOCSolver`].
+pub struct Solver;
 
-    fn solve(part: Part) -> Self::Solution {
-        if let Part::One = part {
-            solve_part_one()
-        } else {
-            solve_part_two()
-        }
+impl AOCSolver for Solver {
+    type Input = Vec<Monkey>;
+    type Params = Params;
+
+    fn parse(source: &InputSource) -> Result<Self::Input, AocError> {
+        parse_monkeys(source)
+    }
+
+    fn part_one(input: &Self::Input, params: &Self::Params) -> Result<Answer, AocError> {
+        solve_part_one(input, params).map(Answer::from)
+    }
+
+    fn part_two(input: &Self::Input, params: &Self::Params) -> Result<Answer, AocError> {
+        solve_part_two(input, params).map(Answer::from)
+    }
+}
+
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add(Day::new(11), Solver);
+}
+
+params! {
+    /// Tunable values for day 11.
+    pub struct Params(11) {
+        /// How many rounds are played in part one.
+        short_game: usize = 20,
+        /// How many rounds are played in part two.
+        long_game: usize = 10000,
+        /// What worry levels are divided by after each inspection in part one.
+        relief: u64 = 3,
     }
 }
 
@@ -36,8 +63,12 @@ struct Operation {
 
 type MonkeyID = usize;
 
+/// The items a monkey throws to another one.
+type Throw = (MonkeyID, Vec<u64>);
+
+/// A monkey, the items it holds and how it decides where to throw them.
 #[derive(Debug, Clone)]
-struct Monkey {
+pub struct Monkey {
     id: MonkeyID,
     items: VecDeque<u64>,
     operation: Operation,
@@ -46,10 +77,11 @@ struct Monkey {
     inspection_count: u64,
 }
 
-impl Default for Monkey {
-    fn default() -> Self {
+impl Monkey {
+    /// A monkey whose notes have only been read up to its `Monkey N:` header.
+    fn new(id: MonkeyID) -> Self {
         Self {
-            id: MonkeyID::MAX,
+            id,
             items: Default::default(),
             operation: Operation {
                 rhs: Value::Number(u64::MIN),
@@ -62,16 +94,24 @@ impl Default for Monkey {
     }
 }
 
+/// Plays a number of rounds of keep-away.
 #[derive(Debug)]
-struct Round {
+pub struct Round {
     num_rounds: usize,
     monkeys: Vec<Monkey>,
     num_monkeys: usize,
     current_monkey_index: usize,
-    super_factor: Option<u64>,
+    super_factor: Option<u128>,
+    relief: u64,
 }
 
 impl Round {
+    /// Plays `num_rounds` rounds with `monkeys`.
+    ///
+    /// Without the worry factor, worry levels are divided by three after every inspection,
+    /// see [`Round::with_relief`]. With it, they are kept small by the product of every
+    /// monkey's divisor instead.
+    #[must_use]
     pub fn new(monkeys: Vec<Monkey>, num_rounds: usize, enable_worry_factor: bool) -> Self {
         Self {
             num_rounds,
@@ -83,25 +123,71 @@ impl Round {
             num_monkeys: monkeys.len(),
             current_monkey_index: 0,
             monkeys,
+            relief: Params::default().relief,
         }
     }
 
-    pub fn run(&mut self) {
+    /// Divides worry levels by `relief` instead of three when the worry factor is disabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `relief` is zero.
+    pub fn with_relief(self, relief: u64) -> Result<Self, AocError> {
+        if relief == 0 {
+            return Err(AocError::no_solution(
+                Day::new(11),
+                "relief must divide worry levels by at least one",
+            ));
+        }
+
+        Ok(Self { relief, ..self })
+    }
+
+    /// Plays every round.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a worry level grows beyond `u64::MAX`, which happens after a few
+    /// rounds with little or no relief.
+    pub fn run(&mut self) -> Result<(), AocError> {
         for _ in 0..self.num_rounds {
             for _ in 0..self.num_monkeys {
-                let (true_dest, false_dest) = self.find_items_from_current_monkey();
+                let (true_dest, false_dest) = self.find_items_from_current_monkey()?;
                 self.update_destination(true_dest);
                 self.update_destination(false_dest);
                 self.next();
             }
         }
+
+        Ok(())
+    }
+
+    /// How many items each monkey has inspected so far, in monkey order.
+    #[must_use]
+    pub fn inspection_counts(&self) -> Vec<u64> {
+        self.monkeys.iter().map(|m| m.inspection_count).collect()
+    }
+
+    /// The product of the two highest inspection counts.
+    #[must_use]
+    pub fn monkey_business(&self) -> u64 {
+        let mut counts = self.inspection_counts();
+
+        counts.sort_unstable_by_key(|&count| std::cmp::Reverse(count));
+
+        counts.into_iter().take(2).product()
     }
 
-    fn find_common_test_factor(monkeys: &[Monkey]) -> u64 {
-        monkeys.iter().fold(1, |acc, m| acc * m.test)
+    /// The product of every monkey's divisor. It is wider than a worry level, so when it is
+    /// too large to keep them small, reducing by it leaves them as they are and
+    /// [`Round::run`] reports the overflow instead of getting the divisibility tests wrong.
+    fn find_common_test_factor(monkeys: &[Monkey]) -> u128 {
+        monkeys
+            .iter()
+            .fold(1, |acc, m| acc.saturating_mul(u128::from(m.test)))
     }
 
-    fn find_items_from_current_monkey(&mut self) -> ((MonkeyID, Vec<u64>), (MonkeyID, Vec<u64>)) {
+    fn find_items_from_current_monkey(&mut self) -> Result<(Throw, Throw), AocError> {
         let current_monkey = self
             .monkeys
             .get_mut(self.current_monkey_index)
@@ -120,16 +206,25 @@ impl Round {
                 current_monkey.operation.op_type,
                 current_monkey.operation.rhs,
             ) {
-                (OpType::Add, Value::Original) => item + item,
-                (OpType::Add, Value::Number(rhs)) => item + rhs,
-                (OpType::Mul, Value::Original) => item * item,
-                (OpType::Mul, Value::Number(rhs)) => item * rhs,
-            };
+                (OpType::Add, Value::Original) => item.checked_add(item),
+                (OpType::Add, Value::Number(rhs)) => item.checked_add(rhs),
+                (OpType::Mul, Value::Original) => item.checked_mul(item),
+                (OpType::Mul, Value::Number(rhs)) => item.checked_mul(rhs),
+            }
+            .ok_or_else(|| {
+                AocError::no_solution(
+                    Day::new(11),
+                    format!(
+                        "monkey {} makes a worry level of {item} overflow",
+                        current_monkey.id
+                    ),
+                )
+            })?;
 
             let worry_level = if let Some(super_factor) = self.super_factor {
-                worry_level % super_factor
+                (u128::from(worry_level) % super_factor) as u64
             } else {
-                worry_level / 3
+                worry_level / self.relief
             };
             current_monkey.inspection_count += 1;
 
@@ -140,16 +235,16 @@ impl Round {
             }
         }
 
-        (true_destination, false_destination)
+        Ok((true_destination, false_destination))
     }
 
-    fn update_destination(&mut self, destination: (MonkeyID, Vec<u64>)) {
+    fn update_destination(&mut self, destination: Throw) {
         let monkey = self
             .monkeys
             .get_mut(destination.0)
             .unwrap_or_else(|| panic!("Get reference to monkey with ID: {}", destination.0));
 
-        monkey.items.extend(destination.1.into_iter());
+        monkey.items.extend(destination.1);
     }
 
     fn next(&mut self) {
@@ -157,141 +252,242 @@ impl Round {
     }
 }
 
-fn parse_monkeys(load_sample: bool) -> Vec<Monkey> {
-    let file = get_day_input(Day::new(11), load_sample);
-
-    let reader = std::io::BufReader::new(file);
+fn parse_monkey_line(line: &Line, monkeys: &mut Vec<Monkey>) -> Result<(), AocError> {
+    let parts = line
+        .text()
+        .split_ascii_whitespace()
+        .map(|part| part.trim_end_matches([',', ':']))
+        .collect::<Vec<_>>();
+    let part = |idx: usize, what: &str| parts.get(idx).copied().ok_or_else(|| line.missing(what));
+
+    let (first, second) = (
+        part(0, "a monkey attribute")?,
+        part(1, "a monkey attribute")?,
+    );
+
+    if first == "Monkey" {
+        monkeys.push(Monkey::new(line.parse(second)?));
+        return Ok(());
+    }
 
-    let mut monkeys = Vec::new();
+    let monkey = monkeys
+        .last_mut()
+        .ok_or_else(|| line.error(first, "expected a `Monkey N:` header"))?;
 
-    let mut monkey = Monkey::default();
-
-    for line in reader.lines().flatten() {
-        if line.is_empty() {
-            monkeys.push(monkey);
-            monkey = Monkey::default();
-        } else {
-            let line = line.trim().replace([',', ':'], "");
-            let parts = line.split_ascii_whitespace().collect::<Vec<_>>();
-
-            if parts[0].starts_with('M') {
-                monkey.id = parts[1].parse().unwrap();
-            } else if parts[0].starts_with('S') {
-                let values = parts[2..].iter().filter_map(|x| x.parse().ok()).collect();
-                monkey.items = values;
-            } else if parts[0].starts_with('O') {
-                let op = if parts[4] == "*" {
-                    OpType::Mul
-                } else {
-                    OpType::Add
-                };
-                let value = if parts[5] == "old" {
-                    Value::Original
-                } else {
-                    Value::Number(parts[5].parse().unwrap())
-                };
-                monkey.operation = Operation {
-                    rhs: value,
-                    op_type: op,
-                }
-            } else if parts[0].starts_with('T') {
-                let test = parts[3].parse().unwrap();
-                monkey.test = test;
-            } else if parts[1] == "true" {
-                monkey.destination.0 = parts[5].parse().unwrap();
-            } else {
-                monkey.destination.1 = parts[5].parse().unwrap();
+    match (first, second) {
+        ("Starting", _) => {
+            monkey.items = parts[2..]
+                .iter()
+                .map(|item| line.parse(item))
+                .collect::<Result<_, _>>()?;
+        }
+        ("Operation", _) => {
+            let op_type = match part(4, "an operator")? {
+                "*" => OpType::Mul,
+                "+" => OpType::Add,
+                op => return Err(line.error(op, "expected `*` or `+`")),
+            };
+            let rhs = match part(5, "an operand")? {
+                "old" => Value::Original,
+                value => Value::Number(line.parse(value)?),
+            };
+            monkey.operation = Operation { rhs, op_type };
+        }
+        ("Test", _) => {
+            let test = part(3, "a divisor")?;
+            monkey.test = line.parse(test)?;
+            if monkey.test == 0 {
+                return Err(line.error(test, "expected a non-zero divisor"));
             }
         }
+        ("If", "true") => monkey.destination.0 = line.parse(part(5, "a monkey ID")?)?,
+        ("If", "false") => monkey.destination.1 = line.parse(part(5, "a monkey ID")?)?,
+        (first, _) => return Err(line.error(first, "expected a monkey attribute")),
     }
 
-    monkeys.push(monkey);
-
-    monkeys
+    Ok(())
 }
 
-fn solve_part_one() -> u64 {
-    let monkeys = parse_monkeys(false);
+/// Reads the notes on each [`Monkey`], separated by blank lines.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read, a line is malformed or a monkey throws to
+/// a monkey that does not exist.
+pub fn parse_monkeys(source: &InputSource) -> Result<Vec<Monkey>, AocError> {
+    let day = Day::new(11);
+    let file = get_day_input(day, source)?;
 
-    let mut round = Round::new(monkeys, 20, false);
+    let mut monkeys = Vec::new();
+
+    for (idx, text) in read_lines(day, file).enumerate() {
+        let text = text?;
 
-    round.run();
+        if !text.trim().is_empty() {
+            parse_monkey_line(&Line::new(day, idx, &text), &mut monkeys)?;
+        }
+    }
 
-    let mut monkeys = round.monkeys.clone();
+    if monkeys.is_empty() {
+        return Err(AocError::no_solution(day, "the input has no monkeys"));
+    }
 
-    monkeys.sort_by(|m1, m2| m2.inspection_count.cmp(&m1.inspection_count));
+    for monkey in &monkeys {
+        if monkey.test == 0 {
+            return Err(AocError::no_solution(
+                day,
+                format!("monkey {} has no test", monkey.id),
+            ));
+        }
+        for (destination, case) in [
+            (monkey.destination.0, "true"),
+            (monkey.destination.1, "false"),
+        ] {
+            if destination == MonkeyID::MAX {
+                return Err(AocError::no_solution(
+                    day,
+                    format!("monkey {} has no `If {case}` target", monkey.id),
+                ));
+            }
+            if destination >= monkeys.len() {
+                return Err(AocError::no_solution(
+                    day,
+                    format!(
+                        "monkey {} throws to monkey {destination}, which does not exist",
+                        monkey.id
+                    ),
+                ));
+            }
+        }
+    }
 
-    monkeys
-        .into_iter()
-        .take(2)
-        .fold(1, |acc, m| acc * m.inspection_count)
+    Ok(monkeys)
 }
 
-fn solve_part_two() -> u64 {
-    let monkeys = parse_monkeys(false);
+/// The level of monkey business after [`Params::short_game`] rounds with relief.
+///
+/// # Errors
+///
+/// Returns an error if relief is zero, or a worry level overflows.
+pub fn solve_part_one(monkeys: &[Monkey], params: &Params) -> Result<u64, AocError> {
+    let mut round =
+        Round::new(monkeys.to_vec(), params.short_game, false).with_relief(params.relief)?;
 
-    let mut round = Round::new(monkeys, 10000, true);
+    round.run()?;
 
-    round.run();
+    Ok(round.monkey_business())
+}
 
-    let mut monkeys = round.monkeys.clone();
+/// The level of monkey business after [`Params::long_game`] rounds without relief.
+///
+/// # Errors
+///
+/// Returns an error if a worry level overflows, which only happens when the product of the
+/// divisors is too large to keep worry levels small.
+pub fn solve_part_two(monkeys: &[Monkey], params: &Params) -> Result<u64, AocError> {
+    let mut round = Round::new(monkeys.to_vec(), params.long_game, true);
 
-    monkeys.sort_by(|m1, m2| m2.inspection_count.cmp(&m1.inspection_count));
+    round.run()?;
 
-    monkeys
-        .into_iter()
-        .take(2)
-        .fold(1, |acc, m| acc * m.inspection_count)
+    Ok(round.monkey_business())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_monkeys, Round};
+    use std::io::Read;
 
-    #[test]
-    fn solve_sample_one() {
-        let monkeys = parse_monkeys(true);
+    use crate::{get_day_input, AOCSolver, Day, InputSource};
 
-        let mut round = Round::new(monkeys, 20, false);
+    use super::{Params, Round, Solver};
 
-        round.run();
-
-        let mut monkeys = round.monkeys.clone();
-
-        monkeys.sort_by(|m1, m2| m2.inspection_count.cmp(&m1.inspection_count));
-
-        let monkey_business = monkeys
-            .into_iter()
-            .take(2)
-            .fold(1, |acc, m| acc * m.inspection_count);
-
-        assert_eq!(10605, monkey_business);
+    sample_tests! {
+        Solver;
+        sample: InputSource::Sample => { One: 10605, Two: 2_713_310_158_u64 },
     }
 
     #[test]
-    fn solve_sample_two() {
-        let monkeys = parse_monkeys(true);
+    fn count_sample_inspections() {
+        let monkeys = Solver::parse(&InputSource::Sample).unwrap();
 
         let mut round = Round::new(monkeys, 10000, true);
 
-        round.run();
+        round.run().unwrap();
 
-        let mut monkeys = round.monkeys;
+        assert_eq!(vec![52166, 47830, 1938, 52013], round.inspection_counts());
+    }
 
-        println!("{:?}", monkeys);
+    #[test]
+    fn play_a_single_round() {
+        let monkeys = Solver::parse(&InputSource::Sample).unwrap();
+        let params = Params {
+            short_game: 1,
+            ..Params::default()
+        };
+
+        assert_eq!(4 * 5, super::solve_part_one(&monkeys, &params).unwrap());
+        assert_eq!(
+            4 * 6,
+            super::solve_part_one(
+                &monkeys,
+                &Params {
+                    relief: 1,
+                    ..params
+                }
+            )
+            .unwrap()
+        );
+        assert!(super::solve_part_one(
+            &monkeys,
+            &Params {
+                relief: 0,
+                ..params
+            }
+        )
+        .is_err());
+    }
 
-        assert_eq!(52166, monkeys[0].inspection_count);
-        assert_eq!(47830, monkeys[1].inspection_count);
-        assert_eq!(1938, monkeys[2].inspection_count);
-        assert_eq!(52013, monkeys[3].inspection_count);
+    #[test]
+    fn report_worry_levels_that_overflow() {
+        let monkeys = Solver::parse(&InputSource::Sample).unwrap();
+        let params = Params {
+            short_game: 1000,
+            relief: 1,
+            ..Params::default()
+        };
 
-        monkeys.sort_by(|m1, m2| m2.inspection_count.cmp(&m1.inspection_count));
+        let error = super::solve_part_one(&monkeys, &params).unwrap_err();
 
-        let monkey_business = monkeys
-            .into_iter()
-            .take(2)
-            .fold(1, |acc, m| acc * m.inspection_count);
+        assert!(error.to_string().contains("overflow"), "{error}");
+    }
 
-        assert_eq!(2713310158, monkey_business);
+    #[test]
+    fn ignore_blank_lines_between_and_after_monkeys() {
+        let mut sample = String::new();
+        get_day_input(Day::new(11), &InputSource::Sample)
+            .unwrap()
+            .read_to_string(&mut sample)
+            .unwrap();
+        let monkeys = Solver::parse(&InputSource::Text(format!("\n{sample}\n\n"))).unwrap();
+
+        assert_eq!(4, monkeys.len());
+    }
+
+    #[test]
+    fn reject_notes_without_monkeys() {
+        let error = |text: &str| {
+            Solver::parse(&InputSource::Text(text.to_string()))
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert_eq!("day 11: the input has no monkeys", error("\n\n"));
+        assert_eq!(
+            "day 11, line 2, column 3: expected a `Monkey N:` header: `Test`",
+            error("\n  Test: divisible by 23\n")
+        );
+        assert_eq!(
+            "day 11: monkey 0 has no `If false` target",
+            error("Monkey 0:\n  Test: divisible by 2\n    If true: throw to monkey 0\n")
+        );
     }
 }
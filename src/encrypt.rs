@@ -0,0 +1,337 @@
+use std::{
+    ffi::OsString,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+
+/// Environment variable holding the key that encrypted inputs are read and written with.
+pub const INPUT_KEY_VAR: &str = "AOC_INPUT_KEY";
+
+/// Environment variable holding the previous key while rotating to a new one.
+pub const OLD_INPUT_KEY_VAR: &str = "AOC_OLD_INPUT_KEY";
+
+/// Marks the file format, in case it ever needs to change.
+const MAGIC: &[u8] = b"AOCENC1";
+
+const NONCE_LEN: usize = 24;
+
+/// A 256-bit key for encrypted inputs, written as 64 hex digits.
+#[derive(Clone, PartialEq, Eq)]
+pub struct InputKey([u8; 32]);
+
+impl InputKey {
+    /// A fresh random key.
+    #[must_use]
+    pub fn generate() -> Self {
+        Self(XChaCha20Poly1305::generate_key(&mut OsRng).into())
+    }
+
+    /// The key in the environment variable `var`, or `None` if it is not set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the variable does not hold 64 hex digits.
+    pub fn from_env(var: &str) -> io::Result<Option<Self>> {
+        let Some(hex) = std::env::var_os(var) else {
+            return Ok(None);
+        };
+
+        hex.to_str()
+            .and_then(|hex| hex.trim().parse().ok())
+            .map(Some)
+            .ok_or_else(|| invalid(format!("${var} must hold 64 hex digits")))
+    }
+
+    /// Encrypts `plaintext`, binding it to `file_name` so that encrypted files cannot be
+    /// swapped for one another.
+    #[must_use]
+    pub fn encrypt(&self, file_name: &str, plaintext: &[u8]) -> Vec<u8> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let payload = Payload {
+            msg: plaintext,
+            aad: file_name.as_bytes(),
+        };
+        let ciphertext = self
+            .cipher()
+            .encrypt(&nonce, payload)
+            .expect("inputs are far below the cipher's size limit");
+
+        [MAGIC, &nonce, &ciphertext].concat()
+    }
+
+    /// Decrypts what [`InputKey::encrypt`] returned for the same `file_name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `data` was encrypted with another key or for another file, or was
+    /// modified since.
+    pub fn decrypt(&self, file_name: &str, data: &[u8]) -> io::Result<Vec<u8>> {
+        let (nonce, ciphertext) = data
+            .strip_prefix(MAGIC)
+            .filter(|rest| rest.len() >= NONCE_LEN)
+            .map(|rest| rest.split_at(NONCE_LEN))
+            .ok_or_else(|| invalid("not an encrypted input".to_string()))?;
+        let payload = Payload {
+            msg: ciphertext,
+            aad: file_name.as_bytes(),
+        };
+
+        self.cipher()
+            .decrypt(XNonce::from_slice(nonce), payload)
+            .map_err(|_| invalid("wrong key, or the file was modified".to_string()))
+    }
+
+    fn cipher(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(&self.0.into())
+    }
+}
+
+impl fmt::Debug for InputKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("InputKey(..)")
+    }
+}
+
+impl fmt::Display for InputKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+impl FromStr for InputKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 64 {
+            return Err(format!(
+                "a key is 64 hex digits. Got {} characters instead.",
+                s.len()
+            ));
+        }
+        // `from_str_radix` alone would also take a sign.
+        let not_hex =
+            || "a key is 64 hex digits. Got something that is not valid hex instead.".to_string();
+        if !s.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(not_hex());
+        }
+
+        let mut key = [0; 32];
+        for (byte, hex) in key.iter_mut().zip(s.as_bytes().chunks(2)) {
+            let hex = std::str::from_utf8(hex).map_err(|_| not_hex())?;
+            *byte = u8::from_str_radix(hex, 16).map_err(|_| not_hex())?;
+        }
+
+        Ok(Self(key))
+    }
+}
+
+/// Where the encrypted copy of the input at `path` is kept: the same path with `.enc` added.
+#[must_use]
+pub fn encrypted_path(path: &Path) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(".enc");
+    PathBuf::from(name)
+}
+
+/// The key in `$AOC_INPUT_KEY`, which encrypted inputs are read with.
+pub(crate) fn input_key() -> io::Result<InputKey> {
+    InputKey::from_env(INPUT_KEY_VAR)?
+        .ok_or_else(|| invalid(format!("set ${INPUT_KEY_VAR} to decrypt it")))
+}
+
+/// Reads the encrypted copy of the input at `path` with `key`.
+pub(crate) fn read_encrypted(path: &Path, key: &InputKey) -> io::Result<Vec<u8>> {
+    let data = fs::read(encrypted_path(path))?;
+
+    key.decrypt(&file_name(path), &data)
+}
+
+/// What [`encrypt_input`] did with an input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encrypted {
+    /// The plain input was encrypted with the key.
+    Written,
+    /// The encrypted copy already holds the plain input under the key, so it was kept as is.
+    Unchanged,
+    /// The encrypted copy was decrypted with the old key and encrypted again with the key.
+    Rotated,
+    /// There is neither a plain input nor an encrypted copy.
+    Missing,
+}
+
+impl fmt::Display for Encrypted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Written => "encrypted",
+            Self::Unchanged => "unchanged",
+            Self::Rotated => "re-encrypted with the new key",
+            Self::Missing => "no input",
+        })
+    }
+}
+
+/// Brings the encrypted copy of the input at `path` up to date with `key`.
+///
+/// The plain input is encrypted if there is one. Otherwise an existing encrypted copy is
+/// decrypted with `old_key` and encrypted again, which rotates the key without needing the
+/// plain inputs. Copies that already decrypt to the same input are left alone, so re-running
+/// does not change committed files.
+///
+/// # Errors
+///
+/// Returns an error if a file cannot be read or written, or the encrypted copy cannot be
+/// decrypted with `old_key` when it is needed.
+pub fn encrypt_input(
+    path: &Path,
+    key: &InputKey,
+    old_key: Option<&InputKey>,
+) -> io::Result<Encrypted> {
+    let name = file_name(path);
+    let encrypted = encrypted_path(path);
+    let existing = match fs::read(&encrypted) {
+        Ok(data) => Some(data),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e),
+    };
+
+    let (plaintext, status) = match (fs::read(path), &existing) {
+        (Ok(plaintext), _) => (plaintext, Encrypted::Written),
+        (Err(e), _) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        (Err(_), None) => return Ok(Encrypted::Missing),
+        (Err(_), Some(data)) => {
+            if key.decrypt(&name, data).is_ok() {
+                return Ok(Encrypted::Unchanged);
+            }
+            let old_key = old_key.ok_or_else(|| {
+                invalid(format!(
+                    "encrypted with another key, set ${OLD_INPUT_KEY_VAR} to rotate it"
+                ))
+            })?;
+            (old_key.decrypt(&name, data)?, Encrypted::Rotated)
+        }
+    };
+
+    if existing.is_some_and(|data| key.decrypt(&name, &data).is_ok_and(|old| old == plaintext)) {
+        return Ok(Encrypted::Unchanged);
+    }
+
+    // Write next to the target first so an interrupted run never leaves a corrupt file.
+    let mut partial = encrypted.clone().into_os_string();
+    partial.push(".part");
+    fs::write(&partial, key.encrypt(&name, &plaintext))?;
+    fs::rename(&partial, &encrypted)?;
+
+    Ok(status)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{encrypt_input, encrypted_path, read_encrypted, Encrypted, InputKey};
+
+    #[test]
+    fn round_trip_and_reject_tampering() {
+        let key = InputKey::generate();
+        let data = key.encrypt("day_1.txt", b"1000\n2000\n");
+
+        assert_eq!(
+            b"1000\n2000\n".to_vec(),
+            key.decrypt("day_1.txt", &data).unwrap()
+        );
+        assert_ne!(data, key.encrypt("day_1.txt", b"1000\n2000\n"));
+
+        assert!(key.decrypt("day_2.txt", &data).is_err());
+        assert!(InputKey::generate().decrypt("day_1.txt", &data).is_err());
+        let mut tampered = data.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(key.decrypt("day_1.txt", &tampered).is_err());
+        assert!(key.decrypt("day_1.txt", b"1000\n").is_err());
+    }
+
+    #[test]
+    fn keys_are_hex_and_never_printed_by_debug() {
+        let key = InputKey::generate();
+        let hex = key.to_string();
+
+        assert_eq!(64, hex.len());
+        assert_eq!(Ok(key.clone()), hex.parse());
+        assert_eq!(Ok(key.clone()), hex.to_uppercase().parse());
+        assert_eq!("InputKey(..)", format!("{key:?}"));
+        assert_eq!(
+            Err("a key is 64 hex digits. Got 3 characters instead.".to_string()),
+            "abc".parse::<InputKey>()
+        );
+        for not_hex in ["g".repeat(64), format!("+{}", &hex[1..])] {
+            assert_eq!(
+                Err(
+                    "a key is 64 hex digits. Got something that is not valid hex instead."
+                        .to_string()
+                ),
+                not_hex.parse::<InputKey>()
+            );
+        }
+    }
+
+    #[test]
+    fn encrypt_rotate_and_read_transparently() {
+        let dir = std::env::temp_dir().join(format!("aoc_encrypt_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("day_1.txt");
+        let (old_key, new_key) = (InputKey::generate(), InputKey::generate());
+
+        assert_eq!(
+            Encrypted::Missing,
+            encrypt_input(&path, &old_key, None).unwrap()
+        );
+
+        fs::write(&path, "1000\n\n2000\n").unwrap();
+        assert_eq!(
+            Encrypted::Written,
+            encrypt_input(&path, &old_key, None).unwrap()
+        );
+        let encrypted = fs::read(encrypted_path(&path)).unwrap();
+        assert_eq!(
+            Encrypted::Unchanged,
+            encrypt_input(&path, &old_key, None).unwrap()
+        );
+        assert_eq!(encrypted, fs::read(encrypted_path(&path)).unwrap());
+
+        fs::remove_file(&path).unwrap();
+        assert!(encrypt_input(&path, &new_key, None).is_err());
+        assert!(encrypt_input(&path, &new_key, Some(&InputKey::generate())).is_err());
+        assert_eq!(
+            Encrypted::Rotated,
+            encrypt_input(&path, &new_key, Some(&old_key)).unwrap()
+        );
+        assert_eq!(
+            Encrypted::Unchanged,
+            encrypt_input(&path, &new_key, Some(&old_key)).unwrap()
+        );
+
+        assert_eq!(
+            b"1000\n\n2000\n".to_vec(),
+            read_encrypted(&path, &new_key).unwrap()
+        );
+        assert!(read_encrypted(&path, &old_key).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
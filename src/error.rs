@@ -0,0 +1,227 @@
+use std::{fmt, io, path::PathBuf, str::FromStr};
+
+use crate::{Day, Part};
+
+/// Why a day could not be solved.
+///
+/// Every error names the day it belongs to, see [`AocError::day`]. New kinds of failure may
+/// be added, so matches need a wildcard arm.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AocError {
+    /// The input could not be opened or read.
+    Io {
+        day: Day,
+        path: Option<PathBuf>,
+        source: io::Error,
+    },
+    /// A line of the input does not match what the parser expects.
+    Parse {
+        day: Day,
+        line: usize,
+        column: usize,
+        text: String,
+        reason: String,
+    },
+    /// The input parsed, but the puzzle has no answer for it.
+    NoSolution { day: Day, reason: String },
+    /// The day has no solver yet.
+    Unsolved(Day),
+    /// The day's solver was left out of this build by disabling its cargo feature.
+    Disabled(Day),
+    /// The solver panicked while parsing the input (`part` is `None`) or solving a part.
+    Panicked {
+        day: Day,
+        part: Option<Part>,
+        message: String,
+    },
+}
+
+impl AocError {
+    /// The error for a day missing from a registry: [`AocError::Disabled`] if its solver
+    /// exists but was not compiled in, [`AocError::Unsolved`] otherwise.
+    pub(crate) fn unsolved(day: Day) -> Self {
+        if crate::DISABLED_DAYS.contains(&day.get()) {
+            Self::Disabled(day)
+        } else {
+            Self::Unsolved(day)
+        }
+    }
+
+    #[cfg_attr(some_days_disabled, allow(dead_code))]
+    pub(crate) fn no_solution(day: Day, reason: impl Into<String>) -> Self {
+        Self::NoSolution {
+            day,
+            reason: reason.into(),
+        }
+    }
+
+    /// The day the error belongs to.
+    #[must_use]
+    pub const fn day(&self) -> Day {
+        match self {
+            Self::Io { day, .. }
+            | Self::Parse { day, .. }
+            | Self::NoSolution { day, .. }
+            | Self::Unsolved(day)
+            | Self::Disabled(day)
+            | Self::Panicked { day, .. } => *day,
+        }
+    }
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io {
+                day,
+                path: Some(path),
+                source,
+            } => write!(f, "day {day}: cannot read {}: {source}", path.display()),
+            Self::Io {
+                day,
+                path: None,
+                source,
+            } => write!(f, "day {day}: cannot read input: {source}"),
+            Self::Parse {
+                day,
+                line,
+                column,
+                text,
+                reason,
+            } => write!(
+                f,
+                "day {day}, line {line}, column {column}: {reason}: `{text}`"
+            ),
+            Self::NoSolution { day, reason } => write!(f, "day {day}: {reason}"),
+            Self::Unsolved(day) => write!(f, "day {day} has not been solved yet"),
+            Self::Disabled(day) => write!(
+                f,
+                "day {day} is not part of this build. Enable the `d{:02}` feature.",
+                day.get()
+            ),
+            Self::Panicked {
+                day,
+                part: Some(part),
+                message,
+            } => write!(f, "day {day}, part {part}: solver panicked: {message}"),
+            Self::Panicked {
+                day,
+                part: None,
+                message,
+            } => write!(f, "day {day}: parser panicked: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for AocError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// A line of puzzle input, used to point parse errors at the offending text.
+#[cfg_attr(some_days_disabled, allow(dead_code))]
+pub(crate) struct Line<'a> {
+    day: Day,
+    number: usize,
+    text: &'a str,
+}
+
+#[cfg_attr(some_days_disabled, allow(dead_code))]
+impl<'a> Line<'a> {
+    /// `index` is the zero-based index of the line in the input.
+    pub const fn new(day: Day, index: usize, text: &'a str) -> Self {
+        Self {
+            day,
+            number: index + 1,
+            text,
+        }
+    }
+
+    pub const fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// Builds an error pointing at `token`, which should be a slice of this line.
+    pub fn error(&self, token: &str, reason: impl Into<String>) -> AocError {
+        AocError::Parse {
+            day: self.day,
+            line: self.number,
+            column: self.column_of(token),
+            text: token.to_owned(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Builds an error for a line that ends before an expected field.
+    pub fn missing(&self, what: &str) -> AocError {
+        AocError::Parse {
+            day: self.day,
+            line: self.number,
+            column: self.text.chars().count() + 1,
+            text: self.text.to_owned(),
+            reason: format!("expected {what}"),
+        }
+    }
+
+    /// Returns the `idx`th whitespace-separated field of the line.
+    pub fn field(&self, idx: usize, what: &str) -> Result<&'a str, AocError> {
+        self.text
+            .split_ascii_whitespace()
+            .nth(idx)
+            .ok_or_else(|| self.missing(what))
+    }
+
+    pub fn parse<T: FromStr>(&self, token: &str) -> Result<T, AocError> {
+        token
+            .parse()
+            .map_err(|_| self.error(token, "expected a number"))
+    }
+
+    fn column_of(&self, token: &str) -> usize {
+        let start = self.text.as_ptr() as usize;
+        let offset = (token.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|offset| offset + token.len() <= self.text.len())
+            .or_else(|| self.text.find(token))
+            .unwrap_or(0);
+
+        self.text[..offset].chars().count() + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Day;
+
+    use super::Line;
+
+    #[test]
+    fn parse_error_points_at_token() {
+        let line = Line::new(Day::new(9), 4, "R x7");
+        let token = line.field(1, "a step count").unwrap();
+
+        let error = line.parse::<u64>(token).unwrap_err();
+
+        assert_eq!(
+            "day 9, line 5, column 3: expected a number: `x7`",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn missing_field_points_past_line_end() {
+        let line = Line::new(Day::new(4), 0, "2-4");
+
+        let error = line.field(1, "a second range").unwrap_err();
+
+        assert_eq!(
+            "day 4, line 1, column 4: expected a second range: `2-4`",
+            error.to_string()
+        );
+    }
+}
@@ -0,0 +1,349 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    process,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{Day, DayBench, Part, Phase, Stats};
+
+/// Where benchmark results are stored unless another file is given.
+pub const DEFAULT_HISTORY_FILE: &str = "bench_history.tsv";
+
+const HEADER: &str =
+    "# commit\ttimestamp\tday\tphase\tsamples\tmean_ns\tmedian_ns\tstd_dev_ns\tmin_ns\tmax_ns\toutliers";
+
+/// One benchmarked phase, tagged with the commit and time of the run it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BenchRecord {
+    /// Short hash of `HEAD`, suffixed with `-dirty` if the working tree had changes.
+    pub commit: String,
+    /// Seconds since the Unix epoch. Records of one run share a timestamp.
+    pub timestamp: u64,
+    pub day: Day,
+    pub phase: Phase,
+    pub stats: Stats,
+}
+
+impl BenchRecord {
+    /// Records every successfully benchmarked phase in `results`.
+    #[must_use]
+    pub fn from_results(commit: &str, timestamp: u64, results: &[DayBench]) -> Vec<Self> {
+        results
+            .iter()
+            .filter_map(|day_bench| Some((day_bench.day, day_bench.phases.as_ref().ok()?)))
+            .flat_map(|(day, phases)| {
+                phases.iter().map(move |phase| Self {
+                    commit: commit.to_owned(),
+                    timestamp,
+                    day,
+                    phase: phase.phase,
+                    stats: phase.stats,
+                })
+            })
+            .collect()
+    }
+
+    fn to_line(&self) -> String {
+        let phase = match self.phase {
+            Phase::Parse => "parse".to_string(),
+            Phase::Part(part) => part.to_string(),
+        };
+        let stats = &self.stats;
+
+        format!(
+            "{}\t{}\t{}\t{phase}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.commit,
+            self.timestamp,
+            self.day,
+            stats.samples,
+            stats.mean.as_nanos(),
+            stats.median.as_nanos(),
+            stats.std_dev.as_nanos(),
+            stats.min.as_nanos(),
+            stats.max.as_nanos(),
+            stats.outliers,
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let fields = line.split('\t').collect::<Vec<_>>();
+        let [commit, timestamp, day, phase, samples, mean, median, std_dev, min, max, outliers] =
+            fields[..]
+        else {
+            return None;
+        };
+
+        let nanos = |field: &str| field.parse().ok().map(Duration::from_nanos);
+        let phase = match phase {
+            "parse" => Phase::Parse,
+            "1" => Phase::Part(Part::One),
+            "2" => Phase::Part(Part::Two),
+            _ => return None,
+        };
+
+        Some(Self {
+            commit: commit.to_owned(),
+            timestamp: timestamp.parse().ok()?,
+            day: Day::try_new(day.parse().ok()?)?,
+            phase,
+            stats: Stats {
+                samples: samples.parse().ok()?,
+                mean: nanos(mean)?,
+                median: nanos(median)?,
+                std_dev: nanos(std_dev)?,
+                min: nanos(min)?,
+                max: nanos(max)?,
+                outliers: outliers.parse().ok()?,
+            },
+        })
+    }
+}
+
+/// Every benchmark run saved to a history file, oldest first.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BenchHistory {
+    pub records: Vec<BenchRecord>,
+}
+
+impl BenchHistory {
+    /// Reads the history stored at `path`. A missing file is an empty history.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or a line is malformed.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e),
+        };
+
+        let records = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(idx, line)| {
+                BenchRecord::from_line(line).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("{}:{}: malformed benchmark record", path.display(), idx + 1),
+                    )
+                })
+            })
+            .collect::<io::Result<_>>()?;
+
+        Ok(Self { records })
+    }
+
+    /// Appends `records` to the history at `path`, creating the file if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn append(path: &Path, records: &[BenchRecord]) -> io::Result<()> {
+        let is_new = !path.exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        if is_new {
+            writeln!(file, "{HEADER}")?;
+        }
+        for record in records {
+            writeln!(file, "{}", record.to_line())?;
+        }
+
+        Ok(())
+    }
+
+    /// The records of the most recent run, restricted to runs of `commit` if given.
+    ///
+    /// `commit` may be any prefix of the saved commit hash.
+    #[must_use]
+    pub fn baseline(&self, commit: Option<&str>) -> Vec<&BenchRecord> {
+        let matches = |record: &&BenchRecord| commit.is_none_or(|c| record.commit.starts_with(c));
+
+        let Some(latest) = self.records.iter().rfind(matches) else {
+            return Vec::new();
+        };
+
+        self.records
+            .iter()
+            .filter(|record| record.commit == latest.commit && record.timestamp == latest.timestamp)
+            .collect()
+    }
+}
+
+/// How one phase performed compared to a baseline run, by median time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Comparison {
+    pub day: Day,
+    pub phase: Phase,
+    pub baseline: Duration,
+    pub current: Duration,
+    /// Whether `current` is slower than `baseline` by more than the threshold.
+    pub regressed: bool,
+}
+
+impl Comparison {
+    /// Relative change from the baseline, e.g. `0.25` for 25% slower, or `None` if the
+    /// baseline took no measurable time to compare against.
+    #[must_use]
+    pub fn change(&self) -> Option<f64> {
+        if self.baseline.is_zero() {
+            return None;
+        }
+
+        Some(self.current.as_secs_f64() / self.baseline.as_secs_f64() - 1.0)
+    }
+}
+
+/// Compares every phase in `results` that also appears in `baseline`.
+///
+/// A phase regressed if its median got slower by more than `threshold`, given as a fraction
+/// (`0.1` allows 10%). A phase whose baseline median is zero never regresses.
+#[must_use]
+pub fn compare(baseline: &[&BenchRecord], results: &[DayBench], threshold: f64) -> Vec<Comparison> {
+    let mut comparisons = Vec::new();
+
+    for day_bench in results {
+        let Ok(phases) = &day_bench.phases else {
+            continue;
+        };
+
+        for phase in phases {
+            let Some(saved) = baseline
+                .iter()
+                .find(|record| record.day == day_bench.day && record.phase == phase.phase)
+            else {
+                continue;
+            };
+
+            let mut comparison = Comparison {
+                day: day_bench.day,
+                phase: phase.phase,
+                baseline: saved.stats.median,
+                current: phase.stats.median,
+                regressed: false,
+            };
+            comparison.regressed = comparison.change().is_some_and(|change| change > threshold);
+            comparisons.push(comparison);
+        }
+    }
+
+    comparisons
+}
+
+/// The short hash of `HEAD`, or `unknown` outside a git checkout.
+#[must_use]
+pub fn current_commit() -> String {
+    let git = |args: &[&str]| {
+        process::Command::new("git")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    };
+
+    let Some(commit) = git(&["rev-parse", "--short", "HEAD"]) else {
+        return "unknown".to_string();
+    };
+
+    match git(&["status", "--porcelain", "--untracked-files=no"]) {
+        Some(status) if !status.is_empty() => format!("{commit}-dirty"),
+        _ => commit,
+    }
+}
+
+/// Seconds since the Unix epoch.
+#[must_use]
+pub fn timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{Day, DayBench, Part, Phase, PhaseBench, Stats};
+
+    use super::{compare, BenchHistory, BenchRecord};
+
+    fn stats(median_us: u64) -> Stats {
+        let median = Duration::from_micros(median_us);
+        Stats {
+            samples: 10,
+            mean: median,
+            median,
+            std_dev: Duration::ZERO,
+            min: median,
+            max: median,
+            outliers: 0,
+        }
+    }
+
+    fn results(parse_us: u64, part_us: u64) -> Vec<DayBench> {
+        vec![DayBench {
+            day: Day::new(9),
+            phases: Ok(vec![
+                PhaseBench {
+                    phase: Phase::Parse,
+                    stats: stats(parse_us),
+                },
+                PhaseBench {
+                    phase: Phase::Part(Part::Two),
+                    stats: stats(part_us),
+                },
+            ]),
+        }]
+    }
+
+    #[test]
+    fn history_round_trips_through_a_file() {
+        let path = std::env::temp_dir().join(format!("aoc_history_{}.tsv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let first = BenchRecord::from_results("abc1234", 100, &results(10, 20));
+        let second = BenchRecord::from_results("def5678-dirty", 200, &results(11, 30));
+        BenchHistory::append(&path, &first).unwrap();
+        BenchHistory::append(&path, &second).unwrap();
+
+        let history = BenchHistory::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!([first.clone(), second.clone()].concat(), history.records);
+        assert_eq!(second.iter().collect::<Vec<_>>(), history.baseline(None));
+        assert_eq!(
+            first.iter().collect::<Vec<_>>(),
+            history.baseline(Some("abc"))
+        );
+        assert!(history.baseline(Some("fff")).is_empty());
+    }
+
+    #[test]
+    fn slower_medians_beyond_threshold_regress() {
+        let saved = BenchRecord::from_results("abc1234", 100, &results(100, 100));
+        let baseline = saved.iter().collect::<Vec<_>>();
+
+        let comparisons = compare(&baseline, &results(105, 150), 0.1);
+
+        let regressed = comparisons.iter().map(|c| c.regressed).collect::<Vec<_>>();
+        assert_eq!(vec![false, true], regressed);
+        assert!((comparisons[1].change().unwrap() - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn zero_baselines_have_no_change() {
+        let saved = BenchRecord::from_results("abc1234", 100, &results(0, 100));
+        let baseline = saved.iter().collect::<Vec<_>>();
+
+        let comparisons = compare(&baseline, &results(5, 100), 0.1);
+
+        assert_eq!(None, comparisons[0].change());
+        assert!(!comparisons[0].regressed);
+        assert_eq!(Some(0.0), comparisons[1].change());
+    }
+}
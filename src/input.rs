@@ -0,0 +1,286 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Cursor, Read},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        RwLock,
+    },
+};
+
+use crate::{AocError, Day};
+
+/// Environment variable that overrides the directory holding `day_N.txt` files.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+const DEFAULT_INPUT_DIR: &str = "res";
+
+static INPUT_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+static SKIP_EMBEDDED: AtomicBool = AtomicBool::new(false);
+
+/// Where a solver reads its puzzle input from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// `day_N.txt` in the input directory.
+    Real,
+    /// `day_N_sample.txt` in the input directory.
+    Sample,
+    /// An explicit file path.
+    Path(PathBuf),
+    /// Standard input.
+    Stdin,
+    /// Input held in memory.
+    Text(String),
+    /// Input embedded in the binary.
+    Bytes(&'static [u8]),
+}
+
+impl InputSource {
+    /// The file this source reads from, if it reads from one.
+    #[must_use]
+    pub fn path(&self, day: Day) -> Option<PathBuf> {
+        match self {
+            Self::Real | Self::Sample => self.file_name(day).map(|name| input_dir().join(name)),
+            Self::Path(path) => Some(path.clone()),
+            Self::Stdin | Self::Text(_) | Self::Bytes(_) => None,
+        }
+    }
+
+    /// The name of the file this source reads from inside the input directory, if any.
+    #[must_use]
+    pub fn file_name(&self, day: Day) -> Option<String> {
+        match self {
+            Self::Real => Some(format!("day_{day}.txt")),
+            Self::Sample => Some(format!("day_{day}_sample.txt")),
+            Self::Path(_) | Self::Stdin | Self::Text(_) | Self::Bytes(_) => None,
+        }
+    }
+
+    /// The copy of this source's file embedded at build time by the `embed-inputs` feature.
+    #[must_use]
+    pub fn embedded(&self, day: Day) -> Option<&'static [u8]> {
+        let name = self.file_name(day)?;
+
+        crate::EMBEDDED_INPUTS
+            .iter()
+            .find(|(embedded, _)| *embedded == name)
+            .map(|(_, bytes)| *bytes)
+    }
+}
+
+impl From<&str> for InputSource {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_owned())
+    }
+}
+
+impl From<String> for InputSource {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<&Path> for InputSource {
+    fn from(path: &Path) -> Self {
+        Self::Path(path.to_owned())
+    }
+}
+
+impl From<PathBuf> for InputSource {
+    fn from(path: PathBuf) -> Self {
+        Self::Path(path)
+    }
+}
+
+/// The directory [`InputSource::Real`] and [`InputSource::Sample`] read from.
+///
+/// This is the directory passed to [`set_input_dir`], then `$AOC_INPUT_DIR`, then `res`.
+#[must_use]
+pub fn input_dir() -> PathBuf {
+    if let Some(dir) = INPUT_DIR.read().ok().and_then(|dir| dir.clone()) {
+        return dir;
+    }
+
+    std::env::var_os(INPUT_DIR_VAR).map_or_else(|| PathBuf::from(DEFAULT_INPUT_DIR), PathBuf::from)
+}
+
+/// Overrides the input directory for the rest of the process.
+pub fn set_input_dir(dir: impl Into<PathBuf>) {
+    if let Ok(mut input_dir) = INPUT_DIR.write() {
+        *input_dir = Some(dir.into());
+    }
+}
+
+/// Reads [`InputSource::Real`] and [`InputSource::Sample`] from the input directory only,
+/// never from their [embedded](InputSource::embedded) copies, for the rest of the process.
+///
+/// The embedded copies are the files as they were at build time, so this is for when the
+/// files on disk are wanted instead, e.g. to pick up edits to them.
+pub fn skip_embedded_inputs() {
+    SKIP_EMBEDDED.store(true, Ordering::Relaxed);
+}
+
+/// Opens the puzzle input for `day` from `source`.
+///
+/// [`InputSource::Real`] and [`InputSource::Sample`] prefer the [embedded](InputSource::embedded)
+/// copy of their file, if any, over the one in the input directory, unless
+/// [`skip_embedded_inputs`] was called. A file that does not exist is read from its encrypted
+/// copy, `day_N.txt.enc`, if there is one.
+///
+/// # Errors
+///
+/// Returns [`AocError::Io`] if the input file cannot be opened.
+pub fn get_day_input(day: Day, source: &InputSource) -> Result<Box<dyn BufRead + '_>, AocError> {
+    let embedded = !SKIP_EMBEDDED.load(Ordering::Relaxed);
+
+    open_day_input(day, source, &input_dir(), embedded)
+}
+
+/// [`get_day_input`] with `dir` as the input directory, trying the embedded copy first if
+/// `embedded` is set.
+fn open_day_input<'a>(
+    day: Day,
+    source: &'a InputSource,
+    dir: &Path,
+    embedded: bool,
+) -> Result<Box<dyn BufRead + 'a>, AocError> {
+    if let Some(bytes) = source.embedded(day).filter(|_| embedded) {
+        return Ok(Box::new(Cursor::new(bytes)));
+    }
+
+    match source {
+        InputSource::Real | InputSource::Sample | InputSource::Path(_) => {
+            let path = match source {
+                InputSource::Path(path) => path.clone(),
+                _ => dir.join(source.file_name(day).unwrap_or_default()),
+            };
+            match File::open(&path) {
+                Ok(file) => Ok(Box::new(BufReader::new(file))),
+                #[cfg(feature = "encrypted-inputs")]
+                Err(e)
+                    if e.kind() == io::ErrorKind::NotFound
+                        && crate::encrypted_path(&path).exists() =>
+                {
+                    let input = crate::encrypt::input_key()
+                        .and_then(|key| crate::encrypt::read_encrypted(&path, &key));
+                    match input {
+                        Ok(input) => Ok(Box::new(Cursor::new(input))),
+                        Err(source) => Err(AocError::Io {
+                            day,
+                            path: Some(crate::encrypted_path(&path)),
+                            source,
+                        }),
+                    }
+                }
+                Err(source) => Err(AocError::Io {
+                    day,
+                    path: Some(path),
+                    source,
+                }),
+            }
+        }
+        InputSource::Stdin => Ok(Box::new(io::stdin().lock())),
+        InputSource::Text(text) => Ok(Box::new(Cursor::new(text.as_bytes()))),
+        InputSource::Bytes(bytes) => Ok(Box::new(Cursor::new(*bytes))),
+    }
+}
+
+/// Reads `reader` line by line, turning read failures into [`AocError::Io`].
+#[cfg_attr(some_days_disabled, allow(dead_code))]
+pub(crate) fn read_lines(
+    day: Day,
+    reader: impl BufRead,
+) -> impl Iterator<Item = Result<String, AocError>> {
+    reader
+        .lines()
+        .map(move |line| line.map_err(|e| read_error(day, e)))
+}
+
+pub(crate) fn read_to_string(day: Day, mut reader: impl Read) -> Result<String, AocError> {
+    let mut res = String::new();
+
+    reader
+        .read_to_string(&mut res)
+        .map_err(|e| read_error(day, e))?;
+
+    Ok(res)
+}
+
+const fn read_error(day: Day, source: io::Error) -> AocError {
+    AocError::Io {
+        day,
+        path: None,
+        source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Day;
+
+    use super::{get_day_input, open_day_input, read_lines, read_to_string, InputSource};
+
+    #[test]
+    fn read_text_and_bytes() {
+        let day = Day::new(1);
+
+        for source in [
+            InputSource::from("1000\n\n2000"),
+            InputSource::Bytes(b"1000\n\n2000"),
+        ] {
+            let reader = get_day_input(day, &source).unwrap();
+            let lines = read_lines(day, reader).collect::<Result<Vec<_>, _>>();
+
+            assert_eq!(vec!["1000", "", "2000"], lines.unwrap());
+        }
+    }
+
+    #[test]
+    fn embed_inputs_only_with_the_feature() {
+        let day = Day::new(1);
+        let sample = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/res/day_1_sample.txt"));
+
+        if cfg!(all(feature = "embed-inputs", feature = "d01")) {
+            assert_eq!(sample.ok().as_deref(), InputSource::Sample.embedded(day));
+        } else {
+            assert_eq!(None, InputSource::Sample.embedded(day));
+        }
+        assert_eq!(None, InputSource::from("1000").embedded(day));
+    }
+
+    #[test]
+    fn embedded_inputs_come_before_the_input_dir() {
+        let day = Day::new(1);
+        let dir = std::env::temp_dir().join(format!("aoc_input_dir_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let read = |embedded| {
+            open_day_input(day, &InputSource::Sample, &dir, embedded)
+                .and_then(|reader| read_to_string(day, reader))
+        };
+
+        let missing = read(true);
+        std::fs::write(dir.join("day_1_sample.txt"), "42\n").unwrap();
+        let preferred = read(true);
+        let on_disk = read(false);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!("42\n", on_disk.unwrap());
+        if let Some(embedded) = InputSource::Sample.embedded(day) {
+            assert_eq!(embedded, missing.unwrap().as_bytes());
+            assert_eq!(embedded, preferred.unwrap().as_bytes());
+        } else {
+            assert!(missing.is_err());
+            assert_eq!("42\n", preferred.unwrap());
+        }
+    }
+
+    #[test]
+    fn missing_file_reports_path() {
+        let source = InputSource::from(std::path::Path::new("no/such/day_1.txt"));
+
+        let error = get_day_input(Day::new(1), &source).err().unwrap();
+
+        assert!(error.to_string().contains("no/such/day_1.txt"));
+    }
+}
@@ -1,112 +1,232 @@
-use std::fs::File;
-
-pub(crate) mod d01;
-pub(crate) mod d02;
-pub(crate) mod d03;
-pub(crate) mod d04;
-pub(crate) mod d05;
-pub(crate) mod d06;
-pub(crate) mod d07;
-pub(crate) mod d08;
-pub(crate) mod d09;
-pub(crate) mod d10;
-pub(crate) mod d11;
-
+//! Solutions to Advent of Code 2022.
+//!
+//! Every solved day lives in its own `dNN` module, which exposes:
+//!
+//! - `Solver`, implementing [`AOCSolver`] to parse the input and solve each part,
+//! - the day's parser, e.g. [`d09::parse_movements`], and the domain types it returns,
+//! - `solve_part_one` and `solve_part_two`, which return the answer in its natural type,
+//! - `Params`, if the day has tunable values, e.g. [`d07::Params`].
+//!
+//! Anything not exported is an implementation detail and may change at any time.
+//!
+//! The domain types can be used beyond the puzzle as stated, e.g. a rope with three knots or
+//! a thousand rounds of keep-away:
+//!
+//! ```
+//! # #[cfg(all(feature = "d09", feature = "d11"))]
+//! # fn main() -> Result<(), aoc2022::AocError> {
+//! use aoc2022::{d09, d11, InputSource};
+//!
+//! let moves = d09::parse_movements(&InputSource::from("R 4\nU 4\nL 3\nD 1\nR 4\n"))?;
+//! let mut rope = d09::State::new(3);
+//! for r#move in moves {
+//!     rope.update(r#move);
+//! }
+//! assert_eq!(6, rope.num_visited_positions());
+//!
+//! let monkeys = d11::parse_monkeys(&InputSource::Sample)?;
+//! let mut round = d11::Round::new(monkeys, 1000, true);
+//! round.run()?;
+//! assert_eq!(vec![5204, 4792, 199, 5192], round.inspection_counts());
+//! # Ok(())
+//! # }
+//! # #[cfg(not(all(feature = "d09", feature = "d11")))]
+//! # fn main() {}
+//! ```
+//!
+//! To run days by number instead, use the [`Registry`] with [`run`] or [`run_parallel`].
+//!
+//! Each day sits behind a `dNN` cargo feature, all of them enabled by default through
+//! `all-days`. Building with `--no-default-features --features d01,d06` leaves the other
+//! solvers, and their dependencies, out of the crate and the [`Registry`].
+
+mod answer;
+mod bench;
+mod client;
+mod config;
+#[cfg(feature = "encrypted-inputs")]
+mod encrypt;
+mod error;
+mod history;
+mod input;
+mod manifest;
+#[macro_use]
+mod params;
+mod registry;
+mod report;
+mod runner;
+mod scaffold;
+mod scrape;
+mod submit;
+mod watch;
+#[cfg(test)]
+#[macro_use]
+mod samples;
+
+#[cfg(feature = "d01")]
+pub mod d01;
+#[cfg(feature = "d02")]
+pub mod d02;
+#[cfg(feature = "d03")]
+pub mod d03;
+#[cfg(feature = "d04")]
+pub mod d04;
+#[cfg(feature = "d05")]
+pub mod d05;
+#[cfg(feature = "d06")]
+pub mod d06;
+#[cfg(feature = "d07")]
+pub mod d07;
+#[cfg(feature = "d08")]
+pub mod d08;
+#[cfg(feature = "d09")]
+pub mod d09;
+#[cfg(feature = "d10")]
+pub mod d10;
+#[cfg(feature = "d11")]
+pub mod d11;
+
+// `register_all` and the tables of disabled days and embedded inputs, see build.rs.
+include!(concat!(env!("OUT_DIR"), "/days.rs"));
+
+pub use answer::Answer;
+pub use bench::{bench, bench_day, BenchOptions, DayBench, Phase, PhaseBench, Stats};
+pub use client::{
+    last_request_file, session_token, Client, ClientError, Fetched, Response, BASE_URL_VAR,
+    DEFAULT_BASE_URL, DEFAULT_INTERVAL, DEFAULT_SESSION_FILE, LAST_REQUEST_FILE, SESSION_VAR,
+};
+pub use config::{Config, DEFAULT_CONFIG_FILE};
+#[cfg(feature = "encrypted-inputs")]
+pub use encrypt::{
+    encrypt_input, encrypted_path, Encrypted, InputKey, INPUT_KEY_VAR, OLD_INPUT_KEY_VAR,
+};
+pub use error::AocError;
+pub use history::{
+    compare, current_commit, timestamp, BenchHistory, BenchRecord, Comparison, DEFAULT_HISTORY_FILE,
+};
+#[cfg_attr(some_days_disabled, allow(unused_imports))]
+pub(crate) use input::read_lines;
+pub(crate) use input::read_to_string;
+pub use input::{
+    get_day_input, input_dir, set_input_dir, skip_embedded_inputs, InputSource, INPUT_DIR_VAR,
+};
+pub use manifest::{checksum, verify, Expected, Manifest, Verdict, Verification};
+pub use params::{set_param, ParamError, ParamInfo, Params};
+pub use registry::{DaySolver, ParsedInput, Registry};
+pub use report::{write_records, Format, Record, Status};
+pub use runner::{quiet_panics, run, run_day, run_parallel, DayRun, PartRun, RunReport};
+pub use scaffold::{module_source, scaffold, Scaffold};
+pub use scrape::{save_sample, update_sample_row, PartDescription, PuzzlePage, SampleFile};
+pub use submit::{submit, Guess, GuessHistory, Outcome, Refusal, SubmitError, DEFAULT_GUESS_FILE};
+pub use watch::{InputWatcher, DEFAULT_POLL_INTERVAL};
+
+/// Solves every registered day on its real input, formatted as `"day: part 1, part 2"`.
 #[must_use]
 pub fn collect_all_solutions() -> Vec<String> {
-    let mut solutions = Vec::new();
-
-    let s1 = d01::Solver::solve(Part::One);
-    let s2 = d01::Solver::solve(Part::Two);
-
-    solutions.push(format!("1: {s1}, {s2}"));
-
-    let s1 = d02::Solver::solve(Part::One);
-    let s2 = d02::Solver::solve(Part::Two);
-
-    solutions.push(format!("2: {s1}, {s2}"));
-
-    let s1 = d03::Solver::solve(Part::One);
-    let s2 = d03::Solver::solve(Part::Two);
-
-    solutions.push(format!("3: {s1}, {s2}"));
-
-    let s1 = d04::Solver::solve(Part::One);
-    let s2 = d04::Solver::solve(Part::Two);
-
-    solutions.push(format!("4: {s1}, {s2}"));
-
-    let s1 = d05::Solver::solve(Part::One);
-    let s2 = d05::Solver::solve(Part::Two);
-
-    solutions.push(format!("5: {s1}, {s2}"));
-
-    let s1 = d06::Solver::solve(Part::One);
-    let s2 = d06::Solver::solve(Part::Two);
-
-    solutions.push(format!("6: {s1}, {s2}"));
-
-    let s1 = d07::Solver::solve(Part::One);
-    let s2 = d07::Solver::solve(Part::Two);
-
-    solutions.push(format!("7: {s1}, {s2}"));
-
-    let s1 = d08::Solver::solve(Part::One);
-    let s2 = d08::Solver::solve(Part::Two);
-
-    solutions.push(format!("8: {s1}, {s2}"));
+    let registry = Registry::global();
 
-    let s1 = d09::Solver::solve(Part::One);
-    let s2 = d09::Solver::solve(Part::Two);
-
-    solutions.push(format!("9: {s1}, {s2}"));
-
-    let s1 = d10::Solver::solve(Part::One);
-    let s2 = d10::Solver::solve(Part::Two);
-
-    solutions.push(format!("10: {s1}, \n{s2}"));
-
-    let s1 = d11::D11Solver::solve(Part::One);
-    let s2 = d11::D11Solver::solve(Part::Two);
+    let mut solutions = Vec::new();
 
-    solutions.push(format!("11: {s1}, {s2}"));
+    let days = registry.days().collect::<Vec<_>>();
+
+    for day_run in run(registry, &days, &Part::ALL, &InputSource::Real).days {
+        let answers = match day_run.parts {
+            Ok(parts) => parts
+                .into_iter()
+                .map(|part| match part.answer {
+                    Ok(answer) if answer.is_multiline() => format!("\n{answer}"),
+                    Ok(answer) => answer.to_string(),
+                    Err(e) => e.to_string(),
+                })
+                .collect(),
+            Err(e) => vec![e.to_string(); Part::ALL.len()],
+        };
+
+        solutions.push(format!("{}: {}", day_run.day, answers.join(", ")));
+    }
 
     solutions
 }
 
-pub(crate) trait AOCSolver {
-    type Solution;
-
-    fn solve(part: Part) -> Self::Solution;
+/// Parses a day's input once and solves either part from it.
+///
+/// # Errors
+///
+/// Every method returns an error if the input cannot be read or parsed, or has no answer.
+pub trait AOCSolver {
+    /// The parsed puzzle input, shared by both parts.
+    type Input;
+
+    /// The day's tunable values, or `()` if it has none.
+    type Params: Params;
+
+    /// Reads and parses the day's input from `source`.
+    fn parse(source: &InputSource) -> Result<Self::Input, AocError>;
+
+    /// Solves part one of the puzzle for the parsed `input`.
+    fn part_one(input: &Self::Input, params: &Self::Params) -> Result<Answer, AocError>;
+
+    /// Solves part two of the puzzle for the parsed `input`.
+    fn part_two(input: &Self::Input, params: &Self::Params) -> Result<Answer, AocError>;
+
+    /// Solves `part`.
+    fn solve(part: Part, input: &Self::Input, params: &Self::Params) -> Result<Answer, AocError> {
+        match part {
+            Part::One => Self::part_one(input, params),
+            Part::Two => Self::part_two(input, params),
+        }
+    }
 }
 
-pub(crate) enum Part {
+/// One of the two parts of a puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Part {
     One,
     Two,
 }
 
+impl Part {
+    pub const ALL: [Self; 2] = [Self::One, Self::Two];
+}
+
+impl std::fmt::Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::One => write!(f, "1"),
+            Self::Two => write!(f, "2"),
+        }
+    }
+}
+
+/// A day of the advent calendar, from 1 to 25.
 #[repr(transparent)]
-#[derive(Clone, Copy)]
-pub(crate) struct Day(u8);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Day(u8);
 
 impl Day {
+    /// # Panics
+    ///
+    /// Panics if `day` is not between 1 and 25.
+    #[must_use]
     pub fn new(day: u8) -> Self {
-        assert!(
-            (1..=25).contains(&day),
-            "day must be between 1 - 25. Got {} instead.",
-            day
-        );
+        Self::try_new(day)
+            .unwrap_or_else(|| panic!("day must be between 1 - 25. Got {day} instead."))
+    }
 
-        Self(day)
+    /// Returns `None` if `day` is not an Advent of Code day.
+    #[must_use]
+    pub fn try_new(day: u8) -> Option<Self> {
+        (1..=25).contains(&day).then_some(Self(day))
     }
-}
 
-pub(crate) fn get_day_input(day: Day, load_sample: bool) -> File {
-    let path = if load_sample {
-        format!("res/day_{}_sample.txt", day.0)
-    } else {
-        format!("res/day_{}.txt", day.0)
-    };
+    #[must_use]
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+}
 
-    File::open(&path).unwrap_or_else(|_| panic!("Opening {path}"))
+impl std::fmt::Display for Day {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
 }
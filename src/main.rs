@@ -1,9 +1,788 @@
-use aoc2022::collect_all_solutions;
+mod cli;
 
-fn main() {
-    let all_solutions = collect_all_solutions();
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    thread,
+};
 
-    for solution in all_solutions {
-        println!("{solution}");
+use aoc2022::{
+    bench, compare, current_commit, input_dir, last_request_file, quiet_panics, run_parallel,
+    save_sample, scaffold, session_token, set_input_dir, set_param, skip_embedded_inputs, submit,
+    timestamp, update_sample_row, verify, write_records, Answer, BenchHistory, BenchRecord, Client,
+    ClientError, Config, Day, DayBench, DayRun, Fetched, GuessHistory, InputSource, InputWatcher,
+    Manifest, Outcome, ParamError, Part, PuzzlePage, Registry, SampleFile, SubmitError,
+    BASE_URL_VAR, DEFAULT_BASE_URL,
+};
+#[cfg(feature = "encrypted-inputs")]
+use aoc2022::{encrypt_input, Encrypted, InputKey, INPUT_KEY_VAR, OLD_INPUT_KEY_VAR};
+use cli::{
+    parse_args, BenchArgs, Command, NewArgs, ScrapeArgs, Selection, ServerArgs, SubmitArgs,
+    VerifyArgs, WatchArgs,
+};
+
+fn main() -> ExitCode {
+    // Panicking solvers are printed with the day they belong to instead.
+    quiet_panics();
+
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let config_path = cli::config_path(&args);
+    let config = match Config::load(&config_path) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("error: cannot load {}: {e}", config_path.display());
+            return ExitCode::from(2);
+        }
+    };
+
+    let command = match parse_args(args, &config) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("error: {e}\n\n{}", cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    if let Command::Run(selection)
+    | Command::Bench(selection, _)
+    | Command::Verify(selection, _)
+    | Command::Fetch(selection, _)
+    | Command::Submit(selection, _)
+    | Command::Scrape(selection, _)
+    | Command::Watch(selection, _) = &command
+    {
+        if selection.explicit_input_dir {
+            skip_embedded_inputs();
+        }
+        for (day, name, value) in &selection.params {
+            match set_param(*day, name, value) {
+                Ok(()) => {}
+                // Commands for other days should not fail over it, e.g. with aoc.toml params.
+                Err(e @ ParamError::UnknownDay(_)) => {
+                    eprintln!("warning: ignoring {day}.{name}={value}: {e}");
+                }
+                Err(e) => {
+                    eprintln!("error: {e}");
+                    return ExitCode::from(2);
+                }
+            }
+        }
+    }
+
+    match command {
+        Command::Run(selection) => run_selection(selection),
+        Command::Bench(selection, args) => bench_selection(selection, args),
+        Command::Verify(selection, args) => verify_selection(selection, &args),
+        Command::Fetch(selection, args) => fetch_selection(selection, &args),
+        Command::Submit(selection, args) => submit_selection(selection, &args),
+        Command::Scrape(selection, args) => scrape_selection(selection, &args),
+        Command::New(selection, args) => new_day(selection, &args),
+        Command::Watch(selection, args) => watch_selection(selection, &args),
+        Command::Encrypt(_, args) if args.new_key => new_key(),
+        Command::Encrypt(selection, _) => encrypt_selection(selection),
+        Command::List => {
+            let registry = Registry::global();
+            for day in registry.days() {
+                println!("{day}");
+                for param in registry.get(day).map(|s| s.params()).unwrap_or_default() {
+                    println!(
+                        "    {}: {} = {}  {}",
+                        param.name, param.kind, param.default, param.doc
+                    );
+                }
+            }
+            ExitCode::SUCCESS
+        }
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+fn run_selection(selection: Selection) -> ExitCode {
+    let Selection {
+        days,
+        parts,
+        input,
+        input_dir,
+        format,
+        jobs,
+        ..
+    } = selection;
+
+    let registry = Registry::global();
+    let days = prepare(registry, days, input_dir);
+
+    let report = run_parallel(registry, &days, &parts, &input, jobs);
+
+    if let Some(format) = format {
+        if let Err(e) = write_records(&mut io::stdout().lock(), &report.records(), format) {
+            eprintln!("error: cannot write results: {e}");
+            return ExitCode::FAILURE;
+        }
+    } else {
+        for day_run in &report.days {
+            print_day(day_run);
+        }
+
+        if jobs > 1 {
+            println!(
+                "total: {:.2?} wall clock, {:.2?} across {jobs} jobs",
+                report.elapsed,
+                report.busy_time()
+            );
+        } else {
+            println!("total: {:.2?}", report.elapsed);
+        }
+    }
+
+    if report.is_success() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Runs every selected day, then only the days whose input changed, until interrupted.
+fn watch_selection(selection: Selection, args: &WatchArgs) -> ExitCode {
+    let Selection {
+        days,
+        parts,
+        input,
+        input_dir,
+        jobs,
+        ..
+    } = selection;
+
+    let registry = Registry::global();
+    let days = prepare(registry, days, input_dir);
+    // The embedded inputs never change, so runs read the watched files instead.
+    skip_embedded_inputs();
+    let mut watcher = InputWatcher::new(&days, &input);
+
+    let mut changed = days;
+    loop {
+        if !changed.is_empty() {
+            // Errors are printed with the day they belong to; the watch goes on.
+            let report = run_parallel(registry, &changed, &parts, &input, jobs);
+            for day_run in &report.days {
+                print_day(day_run);
+            }
+            println!("watching for changes, press Ctrl-C to stop");
+        }
+
+        thread::sleep(args.poll);
+        changed = watcher.changed();
+
+        for &day in &changed {
+            if let Some(path) = watcher.path(day) {
+                println!("\n{} changed", path.display());
+            }
+        }
+    }
+}
+
+fn verify_selection(selection: Selection, args: &VerifyArgs) -> ExitCode {
+    let Selection {
+        days,
+        parts,
+        input,
+        input_dir,
+        ..
+    } = selection;
+
+    let registry = Registry::global();
+    let days = prepare(registry, days, input_dir);
+
+    let path = args
+        .manifest
+        .clone()
+        .unwrap_or_else(|| Manifest::default_path(&input));
+    let mut manifest = match Manifest::load(&path) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            eprintln!("error: cannot load {}: {e}", path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Only the sample answers are checked in; answers for real inputs start out unrecorded.
+    if !args.update && !path.exists() {
+        eprintln!(
+            "note: {} does not exist yet, record the current answers with `verify --update`",
+            path.display()
+        );
+    }
+
+    let verifications = verify(registry, &manifest, &days, &parts, &input);
+
+    for verification in &verifications {
+        println!(
+            "{:>3}  part {}  {}",
+            verification.day.get(),
+            verification.part,
+            verification.verdict
+        );
+    }
+
+    if args.update {
+        let changed = manifest.update(&verifications);
+        if let Err(e) = manifest.save(&path) {
+            eprintln!("error: cannot save {}: {e}", path.display());
+            return ExitCode::FAILURE;
+        }
+        println!("updated {changed} answers in {}", path.display());
+
+        return if verifications
+            .iter()
+            .all(|verification| verification.answer.is_some())
+        {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        };
+    }
+
+    let passed = verifications
+        .iter()
+        .filter(|verification| verification.verdict.is_pass())
+        .count();
+    println!("{passed}/{} passed", verifications.len());
+
+    if passed == verifications.len() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn fetch_selection(selection: Selection, args: &ServerArgs) -> ExitCode {
+    let days = prepare(Registry::global(), selection.days, selection.input_dir);
+    let dir = input_dir();
+
+    let mut client = match connect(args) {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut failed = false;
+
+    for day in days {
+        match client.fetch_input(day, &dir) {
+            Ok(Fetched::Downloaded(path)) => println!("{day:>3}  downloaded {}", path.display()),
+            Ok(Fetched::Cached(path)) => println!("{day:>3}  already in {}", path.display()),
+            Err(e) => {
+                eprintln!("{day:>3}  error: {e}");
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn submit_selection(selection: Selection, args: &SubmitArgs) -> ExitCode {
+    let Selection {
+        days,
+        parts,
+        input,
+        input_dir,
+        ..
+    } = selection;
+
+    let registry = Registry::global();
+    let days = prepare(registry, days, input_dir);
+    let (day, part) = (days[0], parts[0]);
+
+    let answer = match &args.answer {
+        Some(answer) => {
+            let Ok(answer) = answer.parse::<Answer>();
+            answer
+        }
+        None => match registry.solve(day, part, &input) {
+            Ok(answer) => {
+                println!("day {day}, part {part}: {answer}");
+                answer
+            }
+            Err(e) => {
+                eprintln!("error: {e}");
+                return ExitCode::FAILURE;
+            }
+        },
+    };
+
+    let path = args
+        .guesses
+        .clone()
+        .unwrap_or_else(GuessHistory::default_path);
+    let history = match GuessHistory::load(&path) {
+        Ok(history) => history,
+        Err(e) => {
+            eprintln!("error: cannot load {}: {e}", path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let guess = match connect(&args.server)
+        .map_err(SubmitError::from)
+        .and_then(|mut client| submit(&mut client, &history, day, part, &answer))
+    {
+        Ok(guess) => guess,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!("day {day}, part {part}: `{answer}` is {}", guess.outcome);
+
+    if let Err(e) = GuessHistory::append(&path, &guess) {
+        eprintln!("error: cannot save {}: {e}", path.display());
+        return ExitCode::FAILURE;
+    }
+
+    if guess.outcome == Outcome::Correct {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn scrape_selection(selection: Selection, args: &ScrapeArgs) -> ExitCode {
+    let days = prepare(Registry::global(), selection.days, selection.input_dir);
+
+    let manifest_path = Manifest::default_path(&InputSource::Sample);
+    let mut manifest = match Manifest::load(&manifest_path) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            eprintln!("error: cannot load {}: {e}", manifest_path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Saved pages are read instead of downloaded, so only connect without one.
+    let mut client = match &args.page {
+        Some(_) => None,
+        None => match connect(&args.server) {
+            Ok(client) => Some(client),
+            Err(e) => {
+                eprintln!("error: {e}");
+                return ExitCode::FAILURE;
+            }
+        },
+    };
+    let mut failed = false;
+
+    for day in days {
+        let html = match (&mut client, &args.page) {
+            (Some(client), _) => client.puzzle_page(day).map_err(|e| e.to_string()),
+            (None, page) => {
+                let page = page.as_deref().unwrap_or(Path::new(""));
+                fs::read_to_string(page).map_err(|e| format!("cannot read {}: {e}", page.display()))
+            }
+        };
+
+        let page = match html {
+            Ok(html) => PuzzlePage::parse(&html),
+            Err(e) => {
+                eprintln!("{day:>3}  error: {e}");
+                failed = true;
+                continue;
+            }
+        };
+
+        let sample_path = InputSource::Sample.path(day).unwrap_or_default();
+        match save_sample(day, &page, &sample_path, &mut manifest) {
+            Ok((SampleFile::Missing, _)) => {
+                eprintln!("{day:>3}  error: the page has no example");
+                failed = true;
+            }
+            Ok((SampleFile::Differs, _)) => {
+                eprintln!(
+                    "{day:>3}  error: {} holds a different sample, kept it",
+                    sample_path.display()
+                );
+                failed = true;
+            }
+            Ok((status, parts)) => {
+                let written = if status == SampleFile::Written {
+                    "wrote"
+                } else {
+                    "kept"
+                };
+                println!("{day:>3}  {written} {}", sample_path.display());
+
+                if parts.is_empty() {
+                    continue;
+                }
+                // Days without a module get the answers when `aoc2022 new` creates one.
+                let module = args.src.join(format!("d{:02}.rs", day.get()));
+                if !module.exists() {
+                    continue;
+                }
+                match update_sample_row(day, &module, &manifest) {
+                    Ok(true) => {
+                        println!("{day:>3}  wrote the sample answers to {}", module.display())
+                    }
+                    Ok(false) => {}
+                    Err(e) => {
+                        eprintln!("{day:>3}  error: cannot update {}: {e}", module.display());
+                        failed = true;
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!(
+                    "{day:>3}  error: cannot write {}: {e}",
+                    sample_path.display()
+                );
+                failed = true;
+            }
+        }
+    }
+
+    if let Err(e) = manifest.save(&manifest_path) {
+        eprintln!("error: cannot save {}: {e}", manifest_path.display());
+        return ExitCode::FAILURE;
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn new_day(selection: Selection, args: &NewArgs) -> ExitCode {
+    if let Some(input_dir) = selection.input_dir {
+        set_input_dir(input_dir);
+    }
+    let day = selection.days[0];
+
+    let manifest_path = Manifest::default_path(&InputSource::Sample);
+    let manifest = match Manifest::load(&manifest_path) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            eprintln!("error: cannot load {}: {e}", manifest_path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    let sample_path = InputSource::Sample.path(day).unwrap_or_default();
+
+    match scaffold(
+        day,
+        args.title.as_deref(),
+        &args.src,
+        &sample_path,
+        &manifest,
+    ) {
+        Ok(scaffold) => {
+            println!("created {}", scaffold.module.display());
+            if let Some(sample) = scaffold.sample {
+                println!(
+                    "created {}, paste the sample input into it",
+                    sample.display()
+                );
+            }
+            if scaffold.answers.len() < Part::ALL.len() {
+                println!("fill in the sample answers marked TODO in the tests module");
+            }
+            println!("remove the #[ignore] on the sample test once the day is solved");
+            let module = format!("d{:02}", day.get());
+            println!(
+                "declared `pub mod {module};` in lib.rs; the day is always compiled, add \
+                 `#[cfg(feature = \"{module}\")]` to it and `{module} = []` to the features in \
+                 Cargo.toml, and to `all-days`, to make it optional"
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(feature = "encrypted-inputs")]
+fn new_key() -> ExitCode {
+    println!("{}", InputKey::generate());
+    ExitCode::SUCCESS
+}
+
+/// Encrypts the real input of every selected day, or of every day if none are selected.
+#[cfg(feature = "encrypted-inputs")]
+fn encrypt_selection(selection: Selection) -> ExitCode {
+    if let Some(input_dir) = selection.input_dir {
+        set_input_dir(input_dir);
+    }
+
+    let key = match InputKey::from_env(INPUT_KEY_VAR) {
+        Ok(Some(key)) => key,
+        Ok(None) => {
+            eprintln!(
+                "error: set ${INPUT_KEY_VAR} to the key to encrypt with, \
+                 e.g. one from `aoc2022 encrypt --new-key`"
+            );
+            return ExitCode::FAILURE;
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let old_key = match InputKey::from_env(OLD_INPUT_KEY_VAR) {
+        Ok(old_key) => old_key,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let all_days = selection.days.is_empty();
+    let days = if all_days {
+        (1..=25).map(Day::new).collect()
+    } else {
+        selection.days
+    };
+
+    let mut success = true;
+    for day in days {
+        let path = InputSource::Real.path(day).unwrap_or_default();
+
+        match encrypt_input(&path, &key, old_key.as_ref()) {
+            Ok(Encrypted::Missing) if all_days => {}
+            Ok(status) => println!("day {day}: {status}"),
+            Err(e) => {
+                eprintln!("error: day {day}: {}: {e}", path.display());
+                success = false;
+            }
+        }
+    }
+
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+#[cfg(not(feature = "encrypted-inputs"))]
+fn new_key() -> ExitCode {
+    encryption_disabled()
+}
+
+#[cfg(not(feature = "encrypted-inputs"))]
+fn encrypt_selection(_selection: Selection) -> ExitCode {
+    encryption_disabled()
+}
+
+#[cfg(not(feature = "encrypted-inputs"))]
+fn encryption_disabled() -> ExitCode {
+    eprintln!("error: this build cannot encrypt inputs. Enable the `encrypted-inputs` feature.");
+    ExitCode::FAILURE
+}
+
+/// A client for the configured server, using the session token from the environment or file.
+fn connect(args: &ServerArgs) -> Result<Client, ClientError> {
+    let base_url = args
+        .base_url
+        .clone()
+        .or_else(|| std::env::var(BASE_URL_VAR).ok())
+        .unwrap_or_else(|| DEFAULT_BASE_URL.to_string());
+
+    let session = session_token(args.session_file.as_deref())?;
+
+    Ok(Client::new(&base_url, session)?
+        .with_interval(args.interval)
+        .with_last_request_file(last_request_file(args.session_file.as_deref())))
+}
+
+/// Applies the input directory override and expands an empty day list to every solved day.
+fn prepare(registry: &Registry, days: Vec<Day>, input_dir: Option<PathBuf>) -> Vec<Day> {
+    if let Some(input_dir) = input_dir {
+        set_input_dir(input_dir);
+    }
+
+    if days.is_empty() {
+        registry.days().collect()
+    } else {
+        days
+    }
+}
+
+fn bench_selection(selection: Selection, args: BenchArgs) -> ExitCode {
+    let Selection {
+        days,
+        parts,
+        input,
+        input_dir,
+        ..
+    } = selection;
+
+    let registry = Registry::global();
+    let days = prepare(registry, days, input_dir);
+
+    println!(
+        "{:>3}  {:<6} {:>10} {:>10} {:>10} {:>10} {:>10}  outliers",
+        "day", "phase", "mean", "median", "std dev", "min", "max"
+    );
+
+    let results = bench(registry, &days, &parts, &input, args.options);
+
+    for day_bench in &results {
+        print_bench(day_bench);
+    }
+
+    let mut success = results.iter().all(|day_bench| day_bench.phases.is_ok());
+
+    if args.compare {
+        success &= compare_with_history(&args, &results);
+    }
+
+    if args.save {
+        let commit = current_commit();
+        let records = BenchRecord::from_results(&commit, timestamp(), &results);
+        match BenchHistory::append(&args.history, &records) {
+            Ok(()) => println!(
+                "saved {} results for {commit} to {}",
+                records.len(),
+                args.history.display()
+            ),
+            Err(e) => {
+                eprintln!("error: cannot save to {}: {e}", args.history.display());
+                success = false;
+            }
+        }
+    }
+
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Prints how `results` compare to the saved baseline. Returns whether nothing regressed.
+fn compare_with_history(args: &BenchArgs, results: &[DayBench]) -> bool {
+    let history = match BenchHistory::load(&args.history) {
+        Ok(history) => history,
+        Err(e) => {
+            eprintln!("error: cannot load {}: {e}", args.history.display());
+            return false;
+        }
+    };
+
+    let baseline = history.baseline(args.baseline.as_deref());
+    let Some(saved) = baseline.first() else {
+        eprintln!(
+            "error: no saved benchmark run{} in {}",
+            args.baseline
+                .as_ref()
+                .map_or_else(String::new, |commit| format!(" for {commit}")),
+            args.history.display()
+        );
+        return false;
+    };
+
+    println!(
+        "\ncompared to {} (threshold {}%):",
+        saved.commit, args.threshold
+    );
+
+    let comparisons = compare(&baseline, results, f64::from(args.threshold) / 100.0);
+    for comparison in &comparisons {
+        println!(
+            "{:>3}  {:<6} {:>10.2?} -> {:>10.2?} {:>9}{}",
+            comparison.day.get(),
+            comparison.phase,
+            comparison.baseline,
+            comparison.current,
+            comparison.change().map_or_else(
+                || "n/a".to_string(),
+                |change| format!("{:+.1}%", change * 100.0)
+            ),
+            if comparison.regressed {
+                "  REGRESSED"
+            } else {
+                ""
+            },
+        );
+    }
+
+    comparisons.iter().all(|comparison| !comparison.regressed)
+}
+
+fn print_bench(day_bench: &DayBench) {
+    let phases = match &day_bench.phases {
+        Ok(phases) => phases,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return;
+        }
+    };
+
+    for phase in phases {
+        let stats = &phase.stats;
+        println!(
+            "{:>3}  {:<6} {:>10.2?} {:>10.2?} {:>10.2?} {:>10.2?} {:>10.2?}  {}/{}",
+            day_bench.day.get(),
+            phase.phase,
+            stats.mean,
+            stats.median,
+            stats.std_dev,
+            stats.min,
+            stats.max,
+            stats.outliers,
+            stats.samples,
+        );
+    }
+}
+
+fn print_day(day_run: &DayRun) {
+    let parts = match &day_run.parts {
+        Ok(parts) => parts,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return;
+        }
+    };
+
+    // With a part missing, the rest are labelled so it is clear which answer is which.
+    let labelled = parts.iter().any(|part_run| part_run.answer.is_err());
+    let mut answers = Vec::new();
+    // Printed on their own lines after the single-line answers.
+    let mut drawings = Vec::new();
+    let mut timings = vec![format!("parse {:.2?}", day_run.parse_time)];
+
+    for part_run in parts {
+        match &part_run.answer {
+            Ok(answer) if answer.is_multiline() => drawings.push((part_run.part, answer)),
+            Ok(answer) if labelled => answers.push(format!("part {}: {answer}", part_run.part)),
+            Ok(answer) => answers.push(answer.to_string()),
+            Err(e) => eprintln!("error: part {}: {e}", part_run.part),
+        }
+        timings.push(format!("part {} {:.2?}", part_run.part, part_run.elapsed));
+    }
+
+    if !answers.is_empty() {
+        println!("{}: {}", day_run.day, answers.join(", "));
+    } else if !drawings.is_empty() {
+        println!("{}:", day_run.day);
+    }
+    for (part, drawing) in drawings {
+        if labelled {
+            println!("part {part}:");
+        }
+        println!("{}", drawing.to_string().trim_end_matches('\n'));
     }
+    println!("    {}", timings.join(" | "));
 }
@@ -0,0 +1,440 @@
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    get_day_input, input_dir, read_to_string, run_day, Answer, AocError, Day, InputSource, Part,
+    Registry,
+};
+
+const HEADER: &str = "# day\tpart\tchecksum\tkind\tanswer";
+
+/// An answer recorded in a [`Manifest`], with the checksum of the input it was computed from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expected {
+    pub answer: Answer,
+    pub checksum: u64,
+}
+
+/// Expected answers for each day and part, stored as a tab-separated file.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    entries: BTreeMap<(Day, Part), Expected>,
+}
+
+impl Manifest {
+    /// The manifest checked in next to the inputs `source` reads: `answers.tsv` in the input
+    /// directory, or `answers_sample.tsv` for [`InputSource::Sample`].
+    #[must_use]
+    pub fn default_path(source: &InputSource) -> PathBuf {
+        match source {
+            InputSource::Sample => input_dir().join("answers_sample.tsv"),
+            _ => input_dir().join("answers.tsv"),
+        }
+    }
+
+    /// Reads the manifest at `path`. A missing file is an empty manifest.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or a line is malformed.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e),
+        };
+
+        let mut manifest = Self::default();
+
+        for (idx, line) in text.lines().enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, expected) = parse_entry(line).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}:{}: malformed manifest entry", path.display(), idx + 1),
+                )
+            })?;
+            manifest.entries.insert(key, expected);
+        }
+
+        Ok(manifest)
+    }
+
+    /// Writes the manifest to `path`, replacing the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut out = io::BufWriter::new(fs::File::create(path)?);
+
+        writeln!(out, "{HEADER}")?;
+        for ((day, part), expected) in &self.entries {
+            let (kind, answer) = (expected.answer.kind(), escape(&expected.answer.to_string()));
+            writeln!(
+                out,
+                "{day}\t{part}\t{:016x}\t{kind}\t{answer}",
+                expected.checksum
+            )?;
+        }
+
+        out.flush()
+    }
+
+    #[must_use]
+    pub fn get(&self, day: Day, part: Part) -> Option<&Expected> {
+        self.entries.get(&(day, part))
+    }
+
+    pub fn insert(&mut self, day: Day, part: Part, expected: Expected) {
+        self.entries.insert((day, part), expected);
+    }
+
+    /// Records the answer and checksum of every solved part in `verifications`.
+    ///
+    /// Returns how many entries were added or changed.
+    pub fn update(&mut self, verifications: &[Verification]) -> usize {
+        let mut changed = 0;
+
+        for verification in verifications {
+            let (Some(answer), Some(checksum)) = (&verification.answer, verification.checksum)
+            else {
+                continue;
+            };
+
+            let expected = Expected {
+                answer: answer.clone(),
+                checksum,
+            };
+            if self.get(verification.day, verification.part) != Some(&expected) {
+                self.insert(verification.day, verification.part, expected);
+                changed += 1;
+            }
+        }
+
+        changed
+    }
+}
+
+fn parse_entry(line: &str) -> Option<((Day, Part), Expected)> {
+    let mut fields = line.split('\t');
+
+    let day = Day::try_new(fields.next()?.parse().ok()?)?;
+    let part = match fields.next()? {
+        "1" => Part::One,
+        "2" => Part::Two,
+        _ => return None,
+    };
+    let checksum = u64::from_str_radix(fields.next()?, 16).ok()?;
+    let answer = match (fields.next()?, fields.next()) {
+        (kind, Some(answer)) => Answer::from_kind(kind, &unescape(answer)?)?,
+        // Manifests from before the kind was recorded.
+        (answer, None) => {
+            let Ok(answer) = unescape(answer)?.parse();
+            answer
+        }
+    };
+    if fields.next().is_some() {
+        return None;
+    }
+
+    Some(((day, part), Expected { answer, checksum }))
+}
+
+/// Keeps an answer on one line and free of tabs, so multiline answers fit in one field.
+fn escape(answer: &str) -> String {
+    let mut escaped = String::with_capacity(answer.len());
+    for c in answer.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The answer [`escape`] turned into `field`, or `None` for an unknown escape.
+fn unescape(field: &str) -> Option<String> {
+    let mut answer = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            answer.push(c);
+            continue;
+        }
+        answer.push(match chars.next()? {
+            '\\' => '\\',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            _ => return None,
+        });
+    }
+    Some(answer)
+}
+
+/// The FNV-1a hash of a puzzle input, used to tell whether an answer applies to it.
+#[must_use]
+pub fn checksum(input: &[u8]) -> u64 {
+    input.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// How a solved part compares to its [`Manifest`] entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Verdict {
+    Pass,
+    /// The solver gave a different answer for the same input.
+    Mismatch {
+        expected: Answer,
+    },
+    /// The input is not the one the expected answer was recorded for.
+    InputChanged,
+    /// The manifest has no answer for this part.
+    Missing,
+    /// The input could not be read or parsed, or the part returned an error.
+    Fail(String),
+}
+
+impl Verdict {
+    #[must_use]
+    pub const fn is_pass(&self) -> bool {
+        matches!(self, Self::Pass)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pass => write!(f, "pass"),
+            Self::Mismatch { expected } if expected.is_multiline() => {
+                write!(f, "mismatch, expected\n{expected}")
+            }
+            Self::Mismatch { expected } => write!(f, "mismatch, expected {expected}"),
+            Self::InputChanged => write!(f, "input changed since the answer was recorded"),
+            Self::Missing => write!(f, "no expected answer"),
+            Self::Fail(reason) => write!(f, "fail: {reason}"),
+        }
+    }
+}
+
+/// The outcome of checking one part against a [`Manifest`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verification {
+    pub day: Day,
+    pub part: Part,
+    pub verdict: Verdict,
+    /// The answer the solver gave, if it gave one.
+    pub answer: Option<Answer>,
+    /// The checksum of the input, if it could be read.
+    pub checksum: Option<u64>,
+}
+
+/// Solves each of `parts` for each of `days` and checks the answers against `manifest`.
+///
+/// Each input is read once, so [`InputSource::Stdin`] works for a single day.
+#[must_use]
+pub fn verify(
+    registry: &Registry,
+    manifest: &Manifest,
+    days: &[Day],
+    parts: &[Part],
+    source: &InputSource,
+) -> Vec<Verification> {
+    let mut verifications = Vec::new();
+
+    for &day in days {
+        let fail = |e: &AocError, part| Verification {
+            day,
+            part,
+            verdict: Verdict::Fail(e.to_string()),
+            answer: None,
+            checksum: None,
+        };
+
+        let input = match get_day_input(day, source).and_then(|file| read_to_string(day, file)) {
+            Ok(input) => input,
+            Err(e) => {
+                verifications.extend(parts.iter().map(|&part| fail(&e, part)));
+                continue;
+            }
+        };
+        let input_checksum = checksum(input.as_bytes());
+
+        let part_runs = match run_day(registry, day, parts, &InputSource::Text(input)).parts {
+            Ok(part_runs) => part_runs,
+            Err(e) => {
+                verifications.extend(parts.iter().map(|&part| fail(&e, part)));
+                continue;
+            }
+        };
+
+        for part_run in part_runs {
+            let answer = match part_run.answer {
+                Ok(answer) => answer,
+                Err(e) => {
+                    verifications.push(fail(&e, part_run.part));
+                    continue;
+                }
+            };
+
+            let verdict = match manifest.get(day, part_run.part) {
+                None => Verdict::Missing,
+                Some(expected) if expected.checksum != input_checksum => Verdict::InputChanged,
+                // Compared as text, since manifests without kinds read numeric text as numbers.
+                Some(expected) if expected.answer.to_string() != answer.to_string() => {
+                    Verdict::Mismatch {
+                        expected: expected.answer.clone(),
+                    }
+                }
+                Some(_) => Verdict::Pass,
+            };
+
+            verifications.push(Verification {
+                day,
+                part: part_run.part,
+                verdict,
+                answer: Some(answer),
+                checksum: Some(input_checksum),
+            });
+        }
+    }
+
+    verifications
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Answer, AocError, Day, InputSource, Part, Registry};
+
+    #[cfg(feature = "d06")]
+    use super::checksum;
+    use super::{verify, Expected, Manifest, Verdict, Verification};
+
+    #[test]
+    fn checked_in_sample_answers_pass() {
+        let registry = Registry::global();
+        let manifest = Manifest::load(&Manifest::default_path(&InputSource::Sample)).unwrap();
+        // Days made by `aoc2022 new` may have no answers yet, and are unsolved until done.
+        let verifications = registry.days().flat_map(|day| {
+            let parts = Part::ALL
+                .into_iter()
+                .filter(|&part| manifest.get(day, part).is_some())
+                .collect::<Vec<_>>();
+            verify(registry, &manifest, &[day], &parts, &InputSource::Sample)
+        });
+        let unsolved = |verification: &Verification| {
+            verification.verdict == Verdict::Fail(AocError::Unsolved(verification.day).to_string())
+        };
+
+        for verification in verifications.filter(|verification| !unsolved(verification)) {
+            assert_eq!(
+                Verdict::Pass,
+                verification.verdict,
+                "day {} part {}",
+                verification.day,
+                verification.part
+            );
+        }
+    }
+
+    #[cfg(feature = "d06")]
+    #[test]
+    fn verify_reports_mismatch_and_changed_input() {
+        let day = Day::new(6);
+        let input = "bvwbjplbgvbhsrlpgdmjqwftvncz";
+        let mut manifest = Manifest::default();
+        manifest.insert(
+            day,
+            Part::One,
+            Expected {
+                answer: Answer::Integer(4),
+                checksum: checksum(input.as_bytes()),
+            },
+        );
+        manifest.insert(
+            day,
+            Part::Two,
+            Expected {
+                answer: Answer::Integer(23),
+                checksum: checksum(b"something else"),
+            },
+        );
+
+        let verdicts = verify(
+            Registry::global(),
+            &manifest,
+            &[day, Day::new(25)],
+            &Part::ALL,
+            &InputSource::from(input),
+        )
+        .into_iter()
+        .map(|verification| verification.verdict)
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            Verdict::Mismatch {
+                expected: Answer::Integer(4)
+            },
+            verdicts[0]
+        );
+        assert_eq!(Verdict::InputChanged, verdicts[1]);
+        assert!(matches!(verdicts[2], Verdict::Fail(_)));
+    }
+
+    #[test]
+    fn manifest_round_trips_multiline_answers() {
+        let path = std::env::temp_dir().join(format!("aoc_answers_{}.tsv", std::process::id()));
+        let mut manifest = Manifest::default();
+        manifest.insert(
+            Day::new(10),
+            Part::Two,
+            Expected {
+                answer: Answer::from("##..\n..##\n"),
+                checksum: u64::MAX,
+            },
+        );
+        manifest.insert(
+            Day::new(5),
+            Part::One,
+            Expected {
+                answer: Answer::from("a\\nb\tc\\"),
+                checksum: 0,
+            },
+        );
+        manifest.insert(
+            Day::new(5),
+            Part::Two,
+            Expected {
+                answer: Answer::Text("0123".to_string()),
+                checksum: 0,
+            },
+        );
+
+        manifest.save(&path).unwrap();
+        let loaded = Manifest::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(manifest, loaded);
+    }
+
+    #[test]
+    fn read_manifests_without_kinds() {
+        let (_, expected) = super::parse_entry("10\t2\t00000000000000ff\t#.\\n.#\\n").unwrap();
+
+        assert_eq!(Answer::from("#.\n.#\n"), expected.answer);
+        assert!(super::parse_entry("1\t1\t00000000000000ff\tinteger\tCMZ").is_none());
+    }
+}
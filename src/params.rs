@@ -0,0 +1,268 @@
+use std::{collections::BTreeMap, fmt, sync::RwLock};
+
+use crate::{AocError, Day, Registry};
+
+static OVERRIDES: RwLock<BTreeMap<Day, Vec<(String, String)>>> = RwLock::new(BTreeMap::new());
+
+/// A named solver parameter, as declared with `params!`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParamInfo {
+    pub name: &'static str,
+    /// The Rust type of the value, e.g. `u64`.
+    pub kind: &'static str,
+    pub default: String,
+    pub doc: &'static str,
+}
+
+/// A parameter could not be set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamError {
+    /// The day has no solver.
+    UnknownDay(Day),
+    /// The day's solver has no parameter with this name.
+    Unknown { day: Day, name: String },
+    /// The value does not parse as the parameter's type.
+    Invalid {
+        day: Day,
+        name: String,
+        value: String,
+        kind: &'static str,
+    },
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // Says whether the day is unsolved or left out of the build.
+            Self::UnknownDay(day) => write!(f, "{}", AocError::unsolved(*day)),
+            Self::Unknown { day, name } => write!(f, "day {day} has no parameter `{name}`"),
+            Self::Invalid {
+                day,
+                name,
+                value,
+                kind,
+            } => write!(
+                f,
+                "day {day}, parameter `{name}`: expected a {kind}. Got `{value}` instead."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParamError {}
+
+/// The tunable values of one day's solver, with their defaults.
+///
+/// Implemented by the `params!` macro; days without parameters use `()`.
+pub trait Params: Default {
+    /// The day the parameters belong to, if any.
+    fn day() -> Option<Day>;
+
+    /// Every parameter with its type, default value and description.
+    fn info() -> Vec<ParamInfo>;
+
+    /// Sets the parameter called `name` from its textual form.
+    ///
+    /// Returns `false` if there is no such parameter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `value` does not parse as the parameter's type.
+    fn set(&mut self, name: &str, value: &str) -> Result<bool, ParamError>;
+
+    /// The defaults with every override passed to [`set_param`] for this day applied.
+    #[must_use]
+    fn current() -> Self {
+        let mut params = Self::default();
+
+        let Some(day) = Self::day() else {
+            return params;
+        };
+
+        if let Ok(overrides) = OVERRIDES.read() {
+            for (name, value) in overrides.get(&day).into_iter().flatten() {
+                params
+                    .set(name, value)
+                    .expect("overrides are checked by set_param");
+            }
+        }
+
+        params
+    }
+}
+
+impl Params for () {
+    fn day() -> Option<Day> {
+        None
+    }
+
+    fn info() -> Vec<ParamInfo> {
+        Vec::new()
+    }
+
+    fn set(&mut self, _name: &str, _value: &str) -> Result<bool, ParamError> {
+        Ok(false)
+    }
+}
+
+/// Overrides a parameter of `day`'s solver for the rest of the process.
+///
+/// # Errors
+///
+/// Returns an error if the day has no solver, the solver has no such parameter, or `value`
+/// does not parse as the parameter's type.
+pub fn set_param(day: Day, name: &str, value: &str) -> Result<(), ParamError> {
+    let solver = Registry::global()
+        .get(day)
+        .ok_or(ParamError::UnknownDay(day))?;
+
+    if !solver.check_param(name, value)? {
+        return Err(ParamError::Unknown {
+            day,
+            name: name.to_owned(),
+        });
+    }
+
+    if let Ok(mut overrides) = OVERRIDES.write() {
+        overrides
+            .entry(day)
+            .or_default()
+            .push((name.to_owned(), value.to_owned()));
+    }
+
+    Ok(())
+}
+
+/// Declares a day's parameters: a struct with one public field per parameter, its
+/// [`Default`] impl and its [`Params`] impl.
+///
+/// ```ignore
+/// params! {
+///     /// Tunable values for day 7.
+///     pub struct Params(7) {
+///         /// The total size of the disk.
+///         disk_size: u64 = 70_000_000,
+///     }
+/// }
+/// ```
+#[cfg_attr(some_days_disabled, allow(unused_macros))]
+macro_rules! params {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident($day:literal) {
+            $($(#[doc = $doc:literal])* $field:ident: $ty:ty = $default:expr),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct $name {
+            $($(#[doc = $doc])* pub $field: $ty,)+
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self {
+                    $($field: $default,)+
+                }
+            }
+        }
+
+        impl crate::Params for $name {
+            fn day() -> Option<crate::Day> {
+                Some(crate::Day::new($day))
+            }
+
+            fn info() -> Vec<crate::ParamInfo> {
+                let defaults = Self::default();
+
+                vec![$(crate::ParamInfo {
+                    name: stringify!($field),
+                    kind: stringify!($ty),
+                    default: defaults.$field.to_string(),
+                    doc: concat!($($doc),*).trim_start(),
+                },)+]
+            }
+
+            fn set(&mut self, name: &str, value: &str) -> Result<bool, crate::ParamError> {
+                match name {
+                    $(stringify!($field) => {
+                        self.$field = value.parse().map_err(|_| crate::ParamError::Invalid {
+                            day: crate::Day::new($day),
+                            name: name.to_owned(),
+                            value: value.to_owned(),
+                            kind: stringify!($ty),
+                        })?;
+                    })+
+                    _ => return Ok(false),
+                }
+
+                Ok(true)
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Day, ParamError, ParamInfo};
+
+    use super::Params as _;
+
+    params! {
+        /// Parameters for a made-up day.
+        pub struct Knobs(25) {
+            /// How many rounds to play.
+            rounds: usize = 20,
+            /// Whether to apply relief.
+            relief: bool = true,
+        }
+    }
+
+    #[test]
+    fn set_typed_params_by_name() {
+        let mut knobs = Knobs::default();
+
+        assert_eq!(Ok(true), knobs.set("rounds", "10000"));
+        assert_eq!(Ok(true), knobs.set("relief", "false"));
+
+        assert_eq!(
+            Knobs {
+                rounds: 10000,
+                relief: false
+            },
+            knobs
+        );
+        assert!(matches!(
+            knobs.set("rounds", "many"),
+            Err(ParamError::Invalid { kind: "usize", .. })
+        ));
+        assert_eq!(Ok(false), knobs.set("knots", "3"));
+    }
+
+    #[test]
+    fn describe_params() {
+        assert_eq!(
+            ParamInfo {
+                name: "rounds",
+                kind: "usize",
+                default: "20".to_string(),
+                doc: "How many rounds to play.",
+            },
+            Knobs::info()[0]
+        );
+        assert_eq!(Some(Day::new(25)), Knobs::day());
+    }
+
+    #[cfg(feature = "d02")]
+    #[test]
+    fn reject_params_of_unknown_days_and_names() {
+        assert_eq!(
+            Err(ParamError::UnknownDay(Day::new(25))),
+            super::set_param(Day::new(25), "rounds", "1")
+        );
+        assert!(matches!(
+            super::set_param(Day::new(2), "rounds", "1"),
+            Err(ParamError::Unknown { day, .. }) if day == Day::new(2)
+        ));
+    }
+}
@@ -0,0 +1,170 @@
+use std::{any::Any, collections::BTreeMap, sync::OnceLock};
+
+use crate::{AOCSolver, Answer, AocError, Day, InputSource, ParamError, ParamInfo, Params, Part};
+
+/// Puzzle input parsed by a [`DaySolver`], ready to be solved for either part.
+pub struct ParsedInput(Box<dyn Any + Send + Sync>);
+
+/// A solver for one day with its input type erased.
+pub trait DaySolver: Send + Sync {
+    /// Reads and parses the puzzle input from `source`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input cannot be read or parsed.
+    fn parse(&self, source: &InputSource) -> Result<ParsedInput, AocError>;
+
+    /// Solves `part` for input returned by this solver's [`DaySolver::parse`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input has no answer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `input` was parsed by a different solver.
+    fn solve_parsed(&self, part: Part, input: &ParsedInput) -> Result<Answer, AocError>;
+
+    /// Parses `source` and solves `part`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input cannot be read or parsed, or has no answer.
+    fn solve(&self, part: Part, source: &InputSource) -> Result<Answer, AocError> {
+        self.solve_parsed(part, &self.parse(source)?)
+    }
+
+    /// The solver's tunable parameters with their defaults.
+    fn params(&self) -> Vec<ParamInfo> {
+        Vec::new()
+    }
+
+    /// Checks that `value` is valid for the parameter called `name`.
+    ///
+    /// Returns `false` if there is no such parameter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `value` does not parse as the parameter's type.
+    fn check_param(&self, _name: &str, _value: &str) -> Result<bool, ParamError> {
+        Ok(false)
+    }
+}
+
+impl<S> DaySolver for S
+where
+    S: AOCSolver + Send + Sync,
+    S::Input: Send + Sync + 'static,
+{
+    fn parse(&self, source: &InputSource) -> Result<ParsedInput, AocError> {
+        Ok(ParsedInput(Box::new(S::parse(source)?)))
+    }
+
+    fn solve_parsed(&self, part: Part, input: &ParsedInput) -> Result<Answer, AocError> {
+        let input = input
+            .0
+            .downcast_ref::<S::Input>()
+            .expect("input was parsed by a different solver");
+
+        S::solve(part, input, &S::Params::current())
+    }
+
+    fn params(&self) -> Vec<ParamInfo> {
+        S::Params::info()
+    }
+
+    fn check_param(&self, name: &str, value: &str) -> Result<bool, ParamError> {
+        S::Params::default().set(name, value)
+    }
+}
+
+/// Maps each solved [`Day`] to its solver.
+///
+/// Every `dNN` module declared in `lib.rs` is found at build time and adds itself through its
+/// `register` function.
+#[derive(Default)]
+pub struct Registry {
+    solvers: BTreeMap<Day, Box<dyn DaySolver>>,
+}
+
+impl Registry {
+    /// The registry holding every day compiled into this crate.
+    pub fn global() -> &'static Self {
+        static REGISTRY: OnceLock<Registry> = OnceLock::new();
+
+        REGISTRY.get_or_init(|| {
+            let mut registry = Self::default();
+            crate::register_all(&mut registry);
+            registry
+        })
+    }
+
+    /// Adds the solver for `day`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `day` already has a solver.
+    pub fn add(&mut self, day: Day, solver: impl DaySolver + 'static) {
+        let previous = self.solvers.insert(day, Box::new(solver));
+
+        assert!(previous.is_none(), "day {day} is registered twice");
+    }
+
+    /// Registered days in ascending order.
+    pub fn days(&self) -> impl Iterator<Item = Day> + '_ {
+        self.solvers.keys().copied()
+    }
+
+    #[must_use]
+    pub fn get(&self, day: Day) -> Option<&dyn DaySolver> {
+        self.solvers.get(&day).map(AsRef::as_ref)
+    }
+
+    /// Solves a single part of a day.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AocError::Unsolved`] or [`AocError::Disabled`] if `day` is not registered,
+    /// or the solver's error.
+    pub fn solve(&self, day: Day, part: Part, source: &InputSource) -> Result<Answer, AocError> {
+        self.get(day)
+            .ok_or_else(|| AocError::unsolved(day))?
+            .solve(part, source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AocError, Day, InputSource, Part};
+
+    use super::Registry;
+
+    #[test]
+    fn every_module_registers_itself() {
+        let mut days = Registry::global().days().map(Day::get).collect::<Vec<_>>();
+        let mut modules = std::fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/src"))
+            .unwrap()
+            .filter_map(|entry| {
+                let name = entry.ok()?.file_name().into_string().ok()?;
+                name.strip_prefix('d')?
+                    .strip_suffix(".rs")?
+                    .parse::<u8>()
+                    .ok()
+            })
+            .collect::<Vec<_>>();
+        modules.sort_unstable();
+
+        days.extend(crate::DISABLED_DAYS);
+        days.sort_unstable();
+
+        assert!(days.starts_with(&(1..=11).collect::<Vec<_>>()));
+        assert_eq!(modules, days);
+    }
+
+    #[test]
+    fn unregistered_day_is_unsolved() {
+        let result = Registry::global().solve(Day::new(25), Part::One, &InputSource::Sample);
+
+        assert!(matches!(result, Err(AocError::Unsolved(day)) if day == Day::new(25)));
+    }
+}
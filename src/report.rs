@@ -0,0 +1,301 @@
+use std::{
+    fmt,
+    io::{self, Write},
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::{Answer, Day, Part, RunReport};
+
+/// A machine-readable layout for [`Record`]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Format {
+    /// An array with one object per record.
+    Json,
+    /// A header row followed by one row per record.
+    Csv,
+    /// A GitHub-flavoured Markdown table.
+    Markdown,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            "markdown" | "md" => Ok(Self::Markdown),
+            _ => Err(format!(
+                "format must be json, csv or markdown. Got `{s}` instead."
+            )),
+        }
+    }
+}
+
+/// Whether a part produced an answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Status {
+    Ok,
+    /// The input could not be parsed or the part returned an error.
+    Failed,
+    /// The part was not selected.
+    Skipped,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Ok => "ok",
+            Self::Failed => "failed",
+            Self::Skipped => "skipped",
+        })
+    }
+}
+
+/// The outcome of one part of one day, flattened for output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub day: Day,
+    pub part: Part,
+    pub status: Status,
+    pub answer: Option<Answer>,
+    /// Time spent solving the part, excluding parsing.
+    pub elapsed: Duration,
+    pub error: Option<String>,
+}
+
+impl RunReport {
+    /// One record for every part of every day in the report, including skipped parts.
+    #[must_use]
+    pub fn records(&self) -> Vec<Record> {
+        let mut records = Vec::new();
+
+        for day_run in &self.days {
+            for part in Part::ALL {
+                let mut record = Record {
+                    day: day_run.day,
+                    part,
+                    status: Status::Skipped,
+                    answer: None,
+                    elapsed: Duration::ZERO,
+                    error: None,
+                };
+
+                match &day_run.parts {
+                    Ok(parts) => {
+                        if let Some(part_run) = parts.iter().find(|run| run.part == part) {
+                            record.elapsed = part_run.elapsed;
+                            match &part_run.answer {
+                                Ok(answer) => {
+                                    record.status = Status::Ok;
+                                    record.answer = Some(answer.clone());
+                                }
+                                Err(e) => {
+                                    record.status = Status::Failed;
+                                    record.error = Some(e.to_string());
+                                }
+                            }
+                        }
+                    }
+                    Err(e) if self.parts.contains(&part) => {
+                        record.status = Status::Failed;
+                        record.error = Some(e.to_string());
+                    }
+                    Err(_) => {}
+                }
+
+                records.push(record);
+            }
+        }
+
+        records
+    }
+}
+
+/// Writes `records` to `out` in `format`.
+///
+/// # Errors
+///
+/// Returns an error if writing to `out` fails.
+pub fn write_records(out: &mut impl Write, records: &[Record], format: Format) -> io::Result<()> {
+    match format {
+        Format::Json => write_json(out, records),
+        Format::Csv => write_csv(out, records),
+        Format::Markdown => write_markdown(out, records),
+    }
+}
+
+fn write_json(out: &mut impl Write, records: &[Record]) -> io::Result<()> {
+    let optional =
+        |value: Option<String>| value.map_or_else(|| "null".to_string(), |v| json_string(&v));
+
+    writeln!(out, "[")?;
+    for (idx, record) in records.iter().enumerate() {
+        let separator = if idx + 1 < records.len() { "," } else { "" };
+        writeln!(
+            out,
+            r#"  {{"day": {}, "part": {}, "status": "{}", "answer": {}, "time_ns": {}, "error": {}}}{separator}"#,
+            record.day,
+            record.part,
+            record.status,
+            optional(record.answer.as_ref().map(ToString::to_string)),
+            record.elapsed.as_nanos(),
+            optional(record.error.clone()),
+        )?;
+    }
+    writeln!(out, "]")
+}
+
+fn write_csv(out: &mut impl Write, records: &[Record]) -> io::Result<()> {
+    writeln!(out, "day,part,status,answer,time_ns,error")?;
+    for record in records {
+        writeln!(
+            out,
+            "{},{},{},{},{},{}",
+            record.day,
+            record.part,
+            record.status,
+            csv_field(
+                &record
+                    .answer
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_default()
+            ),
+            record.elapsed.as_nanos(),
+            csv_field(record.error.as_deref().unwrap_or_default()),
+        )?;
+    }
+    Ok(())
+}
+
+fn write_markdown(out: &mut impl Write, records: &[Record]) -> io::Result<()> {
+    writeln!(out, "| Day | Part | Status | Answer | Time |")?;
+    writeln!(out, "| --: | ---: | ------ | ------ | ---: |")?;
+    for record in records {
+        let answer = match (&record.answer, &record.error) {
+            // Backticks would show the `<br>` tags literally.
+            (Some(answer), _) if answer.is_multiline() => {
+                format!("<code>{}</code>", markdown_cell(&answer.to_string()))
+            }
+            (Some(answer), _) => format!("`{}`", markdown_cell(&answer.to_string())),
+            (None, Some(error)) => markdown_cell(error),
+            (None, None) => String::new(),
+        };
+        let time = match record.status {
+            Status::Skipped => String::new(),
+            Status::Ok | Status::Failed => format!("{:.2?}", record.elapsed),
+        };
+        writeln!(
+            out,
+            "| {} | {} | {} | {answer} | {time} |",
+            record.day, record.part, record.status,
+        )?;
+    }
+    Ok(())
+}
+
+fn json_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", u32::from(c))),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_owned()
+    }
+}
+
+/// Keeps multiline answers, such as the day 10 screen, inside a single table cell.
+fn markdown_cell(text: &str) -> String {
+    text.trim_end_matches('\n')
+        .replace('|', "\\|")
+        .replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "d05")]
+    use crate::{run, Day, InputSource, Part, Registry};
+
+    #[cfg(feature = "d05")]
+    use super::{write_records, Format, Status};
+
+    #[cfg(feature = "d05")]
+    fn render(format: Format) -> String {
+        let days = [Day::new(5), Day::new(25)];
+        let report = run(
+            Registry::global(),
+            &days,
+            &[Part::One],
+            &InputSource::Sample,
+        );
+
+        let records = report.records();
+        let statuses = records.iter().map(|r| r.status).collect::<Vec<_>>();
+        assert_eq!(
+            vec![Status::Ok, Status::Skipped, Status::Failed, Status::Skipped],
+            statuses
+        );
+
+        let mut out = Vec::new();
+        write_records(&mut out, &records, format).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[cfg(feature = "d05")]
+    #[test]
+    fn write_json() {
+        let json = render(Format::Json);
+
+        assert!(json.starts_with("[\n"));
+        assert!(
+            json.contains(r#"{"day": 5, "part": 1, "status": "ok", "answer": "CMZ", "time_ns": "#)
+        );
+        assert!(
+            json.contains(r#""status": "skipped", "answer": null, "time_ns": 0, "error": null}"#)
+        );
+        assert!(json.contains(r#""error": "day 25 has not been solved yet"}"#));
+        assert!(json.ends_with("}\n]\n"));
+    }
+
+    #[cfg(feature = "d05")]
+    #[test]
+    fn write_csv_and_markdown() {
+        let csv = render(Format::Csv);
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!("day,part,status,answer,time_ns,error", lines[0]);
+        assert!(lines[1].starts_with("5,1,ok,CMZ,"));
+        assert_eq!("5,2,skipped,,0,", lines[2]);
+
+        let markdown = render(Format::Markdown);
+        assert!(markdown.contains("| 5 | 1 | ok | `CMZ` |"));
+        assert!(markdown.contains("| 5 | 2 | skipped |  |  |"));
+        assert!(markdown.contains("| 25 | 1 | failed | day 25 has not been solved yet |"));
+    }
+
+    #[test]
+    fn escape_multiline_answers() {
+        assert_eq!("\"#.\\n.#\"", super::json_string("#.\n.#"));
+        assert_eq!("\"a,\"\"b\"\"\"", super::csv_field("a,\"b\""));
+        assert_eq!("#.<br>.#", super::markdown_cell("#.\n.#\n"));
+    }
+}
@@ -0,0 +1,338 @@
+use std::{
+    any::Any,
+    cell::Cell,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Once,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{Answer, AocError, Day, InputSource, Part, Registry};
+
+/// The outcome of solving one part, and how long it took.
+#[derive(Debug)]
+pub struct PartRun {
+    pub part: Part,
+    pub answer: Result<Answer, AocError>,
+    pub elapsed: Duration,
+}
+
+/// The outcome of parsing and solving one day, with per-phase timings.
+#[derive(Debug)]
+pub struct DayRun {
+    pub day: Day,
+    /// Time spent reading and parsing the input.
+    pub parse_time: Duration,
+    /// The solved parts, or the error that stopped the input from being parsed.
+    pub parts: Result<Vec<PartRun>, AocError>,
+}
+
+impl DayRun {
+    /// Parse time plus the time spent on every part.
+    #[must_use]
+    pub fn total(&self) -> Duration {
+        let parts = self.parts.iter().flatten().map(|part| part.elapsed);
+
+        self.parse_time + parts.sum::<Duration>()
+    }
+}
+
+/// The outcome of running several days.
+#[derive(Debug)]
+pub struct RunReport {
+    pub days: Vec<DayRun>,
+    /// The parts that were selected for every day.
+    pub parts: Vec<Part>,
+    /// Wall-clock time for the whole run.
+    pub elapsed: Duration,
+}
+
+impl RunReport {
+    /// Whether every selected day parsed and every part was solved.
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.days.iter().all(|day| {
+            day.parts
+                .as_ref()
+                .is_ok_and(|parts| parts.iter().all(|part| part.answer.is_ok()))
+        })
+    }
+
+    /// The time spent on every day added up. Exceeds [`RunReport::elapsed`] when days ran in
+    /// parallel.
+    #[must_use]
+    pub fn busy_time(&self) -> Duration {
+        self.days.iter().map(DayRun::total).sum()
+    }
+}
+
+/// Parses the input for `day` once and solves each of `parts` with it, timing every phase.
+///
+/// A panic while parsing or solving becomes [`AocError::Panicked`]. The panic hook still
+/// prints it as usual unless [`quiet_panics`] was called.
+#[must_use]
+pub fn run_day(registry: &Registry, day: Day, parts: &[Part], source: &InputSource) -> DayRun {
+    let Some(solver) = registry.get(day) else {
+        return DayRun {
+            day,
+            parse_time: Duration::ZERO,
+            parts: Err(AocError::unsolved(day)),
+        };
+    };
+
+    let start = Instant::now();
+    let input = isolate(day, None, || solver.parse(source));
+    let parse_time = start.elapsed();
+
+    let parts = input.map(|input| {
+        parts
+            .iter()
+            .map(|&part| {
+                let start = Instant::now();
+                let answer = isolate(day, Some(part), || solver.solve_parsed(part, &input));
+                PartRun {
+                    part,
+                    answer,
+                    elapsed: start.elapsed(),
+                }
+            })
+            .collect()
+    });
+
+    DayRun {
+        day,
+        parse_time,
+        parts,
+    }
+}
+
+/// Runs `f`, turning a panic into [`AocError::Panicked`] so one broken solver cannot take
+/// down the other days.
+pub(crate) fn isolate<T>(
+    day: Day,
+    part: Option<Part>,
+    f: impl FnOnce() -> Result<T, AocError>,
+) -> Result<T, AocError> {
+    ISOLATED.with(|isolated| isolated.set(isolated.get() + 1));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    ISOLATED.with(|isolated| isolated.set(isolated.get() - 1));
+
+    result.unwrap_or_else(|payload| {
+        Err(AocError::Panicked {
+            day,
+            part,
+            message: panic_message(payload.as_ref()),
+        })
+    })
+}
+
+thread_local! {
+    /// How many [`isolate`] calls are running on this thread.
+    static ISOLATED: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Keeps the panic hook from printing the panics of solvers, which the runner reports as
+/// [`AocError::Panicked`] itself.
+///
+/// This wraps the process-wide panic hook, once, in one that passes on every other panic to
+/// the hook set before it, so call it after setting up your own hook, if any. The hook is
+/// global and days run on several threads at once, so it is not swapped around each solver.
+pub fn quiet_panics() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if ISOLATED.with(Cell::get) == 0 {
+                previous(info);
+            }
+        }));
+    });
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| (*message).to_owned())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Runs each of `days` in order.
+///
+/// Panicking solvers are reported as with [`run_day`].
+#[must_use]
+pub fn run(registry: &Registry, days: &[Day], parts: &[Part], source: &InputSource) -> RunReport {
+    let start = Instant::now();
+
+    let days = days
+        .iter()
+        .map(|&day| run_day(registry, day, parts, source))
+        .collect();
+
+    RunReport {
+        days,
+        parts: parts.to_vec(),
+        elapsed: start.elapsed(),
+    }
+}
+
+/// Runs `days` on up to `jobs` threads, one day at a time per thread.
+///
+/// The report lists the days in the order given, exactly as [`run`] would. Panicking solvers
+/// are reported as with [`run_day`].
+#[must_use]
+pub fn run_parallel(
+    registry: &Registry,
+    days: &[Day],
+    parts: &[Part],
+    source: &InputSource,
+    jobs: usize,
+) -> RunReport {
+    let jobs = jobs.min(days.len());
+    if jobs <= 1 {
+        return run(registry, days, parts, source);
+    }
+
+    let start = Instant::now();
+    let next = AtomicUsize::new(0);
+
+    let mut finished = thread::scope(|scope| {
+        let workers = (0..jobs)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut finished = Vec::new();
+                    loop {
+                        let idx = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(&day) = days.get(idx) else {
+                            break finished;
+                        };
+                        finished.push((idx, run_day(registry, day, parts, source)));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("solver panics are caught by run_day"))
+            .collect::<Vec<_>>()
+    });
+
+    finished.sort_unstable_by_key(|(idx, _)| *idx);
+
+    RunReport {
+        days: finished.into_iter().map(|(_, day_run)| day_run).collect(),
+        parts: parts.to_vec(),
+        elapsed: start.elapsed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "d01")]
+    use crate::{Answer, AocError, DaySolver, ParsedInput};
+    use crate::{Day, InputSource, Part, Registry};
+
+    #[cfg(feature = "d01")]
+    use super::run;
+    use super::run_parallel;
+
+    /// Parses fine for part 1 but panics on part 2, and panics while parsing stdin.
+    #[cfg(feature = "d01")]
+    struct Fragile;
+
+    #[cfg(feature = "d01")]
+    impl DaySolver for Fragile {
+        fn parse(&self, source: &InputSource) -> Result<ParsedInput, AocError> {
+            assert!(*source != InputSource::Stdin, "unexpected line");
+            Registry::global().get(Day::new(1)).unwrap().parse(source)
+        }
+
+        fn solve_parsed(&self, part: Part, input: &ParsedInput) -> Result<Answer, AocError> {
+            assert!(part == Part::One, "index out of bounds");
+            Registry::global()
+                .get(Day::new(1))
+                .unwrap()
+                .solve_parsed(part, input)
+        }
+    }
+
+    #[cfg(feature = "d01")]
+    #[test]
+    fn run_reports_answers_and_failures() {
+        let days = [Day::new(1), Day::new(25)];
+
+        let report = run(Registry::global(), &days, &Part::ALL, &InputSource::Sample);
+
+        let answers = report.days[0].parts.as_ref().unwrap();
+        assert_eq!(&Answer::Integer(24000), answers[0].answer.as_ref().unwrap());
+        assert_eq!(&Answer::Integer(45000), answers[1].answer.as_ref().unwrap());
+        assert!(report.days[0].total() >= report.days[0].parse_time);
+
+        assert!(report.days[1].parts.is_err());
+        assert!(!report.is_success());
+    }
+
+    #[test]
+    fn parallel_run_matches_sequential() {
+        let registry = Registry::global();
+        let days = registry.days().chain([Day::new(25)]).collect::<Vec<_>>();
+        let answers = |jobs| {
+            let report = run_parallel(registry, &days, &Part::ALL, &InputSource::Sample, jobs);
+            report
+                .days
+                .iter()
+                .map(|day_run| match &day_run.parts {
+                    Ok(parts) => parts
+                        .iter()
+                        .map(|part| {
+                            (
+                                day_run.day,
+                                part.answer.as_ref().map_err(ToString::to_string).cloned(),
+                            )
+                        })
+                        .collect(),
+                    Err(e) => vec![(day_run.day, Err(e.to_string()))],
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let sequential = answers(1);
+        assert_eq!(sequential, answers(4));
+        assert_eq!(sequential, answers(64));
+    }
+
+    #[cfg(feature = "d01")]
+    #[test]
+    fn panics_become_failures() {
+        let mut registry = Registry::default();
+        registry.add(Day::new(1), Fragile);
+        registry.add(Day::new(2), Fragile);
+
+        let report = run(
+            &registry,
+            &[Day::new(1), Day::new(2)],
+            &Part::ALL,
+            &InputSource::Sample,
+        );
+
+        let parts = report.days[0].parts.as_ref().unwrap();
+        assert_eq!(&Answer::Integer(24000), parts[0].answer.as_ref().unwrap());
+        assert_eq!(
+            "day 1, part 2: solver panicked: index out of bounds",
+            parts[1].answer.as_ref().unwrap_err().to_string()
+        );
+        assert!(report.days[1].parts.is_ok());
+        assert!(!report.is_success());
+
+        let report = run(&registry, &[Day::new(1)], &Part::ALL, &InputSource::Stdin);
+        assert!(matches!(
+            report.days[0].parts,
+            Err(AocError::Panicked { part: None, .. })
+        ));
+    }
+}
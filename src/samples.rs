@@ -0,0 +1,51 @@
+//! Table-driven tests for the puzzle samples.
+
+/// Generates one test per row that parses the sample once and checks the listed parts
+/// against the solver's real `part_one`/`part_two`, with default parameters.
+///
+/// A row is `name: source => { Part: expected, ... }`. `source` is anything that converts
+/// into an `InputSource`: `InputSource::Sample` for `res/day_N_sample.txt`, another sample
+/// file's path, or the sample text itself. `expected` is anything that converts into an
+/// `Answer`. Attributes before a row, e.g. `#[ignore]`, go on its test.
+///
+/// ```ignore
+/// sample_tests! {
+///     super::Solver;
+///     sample: InputSource::Sample => { One: 7, Two: 19 },
+///     #[ignore = "part two is not solved yet"]
+///     second_sample: "bvwbjplbgvbhsrlpgdmjqwftvncz" => { One: 5, Two: 23 },
+/// }
+/// ```
+#[cfg_attr(some_days_disabled, allow(unused_macros))]
+macro_rules! sample_tests {
+    (
+        $solver:ty;
+        $(
+            $(#[$attr:meta])*
+            $name:ident: $source:expr => { $($part:ident: $expected:expr),+ $(,)? }
+        ),+ $(,)?
+    ) => {
+        $(
+            #[test]
+            $(#[$attr])*
+            fn $name() {
+                let source = crate::InputSource::from($source);
+                let input = <$solver as crate::AOCSolver>::parse(&source).unwrap();
+
+                $(
+                    assert_eq!(
+                        crate::Answer::from($expected),
+                        <$solver as crate::AOCSolver>::solve(
+                            crate::Part::$part,
+                            &input,
+                            &Default::default(),
+                        )
+                        .unwrap(),
+                        "part {}",
+                        crate::Part::$part,
+                    );
+                )+
+            }
+        )+
+    };
+}
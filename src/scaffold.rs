@@ -0,0 +1,207 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{scrape::sample_answers, Day, Manifest, Part};
+
+const TEMPLATE: &str = include_str!("../templates/day.rs");
+
+/// The files created for a new day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scaffold {
+    pub module: PathBuf,
+    /// The sample file, if it did not exist yet.
+    pub sample: Option<PathBuf>,
+    /// The parts whose sample answers were taken from the manifest.
+    pub answers: Vec<Part>,
+}
+
+/// The source of a new solver module for `day`, with sample tests for the answers in
+/// `manifest`.
+///
+/// The sample test is ignored until the day is solved, and parts without a recorded answer
+/// expect `"TODO"` until filled in.
+#[must_use]
+pub fn module_source(day: Day, title: Option<&str>, manifest: &Manifest) -> String {
+    let answers = sample_answers(day, manifest, None);
+
+    TEMPLATE
+        .replace("@DAY@", &day.to_string())
+        .replace(
+            "@TITLE@",
+            &title.map(|title| format!(": {title}")).unwrap_or_default(),
+        )
+        .replace("@SAMPLE_ANSWERS@", &answers)
+}
+
+/// Creates `dNN.rs` in `src_dir`, declares it in `lib.rs` next to the other days, and creates
+/// an empty sample file at `sample_path` if there is none.
+///
+/// The build script registers every declared module, so nothing else is edited.
+///
+/// # Errors
+///
+/// Returns [`io::ErrorKind::AlreadyExists`] if the day already has a module, or an error if a
+/// file cannot be read or written.
+pub fn scaffold(
+    day: Day,
+    title: Option<&str>,
+    src_dir: &Path,
+    sample_path: &Path,
+    manifest: &Manifest,
+) -> io::Result<Scaffold> {
+    let module = src_dir.join(format!("d{:02}.rs", day.get()));
+    let lib_path = src_dir.join("lib.rs");
+    let lib = fs::read_to_string(&lib_path)?;
+
+    // `create_new` never replaces an existing day, even one created concurrently.
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&module)
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::AlreadyExists => {
+                io::Error::new(e.kind(), format!("{} already exists", module.display()))
+            }
+            _ => e,
+        })?;
+    file.write_all(module_source(day, title, manifest).as_bytes())?;
+    if let Some(lib) = declare_module(&lib, day) {
+        fs::write(&lib_path, lib)?;
+    }
+
+    let sample = if sample_path.exists() {
+        None
+    } else {
+        if let Some(dir) = sample_path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(sample_path, "")?;
+        Some(sample_path.to_owned())
+    };
+
+    let answers = Part::ALL
+        .into_iter()
+        .filter(|&part| manifest.get(day, part).is_some())
+        .collect();
+
+    Ok(Scaffold {
+        module,
+        sample,
+        answers,
+    })
+}
+
+/// `lib`, the source of `lib.rs`, with `pub mod dNN;` added after the days before `day`, or
+/// `None` if it already declares the module.
+fn declare_module(lib: &str, day: Day) -> Option<String> {
+    let declaration = format!("pub mod d{:02};", day.get());
+    let mut lines = lib.lines().collect::<Vec<_>>();
+    let declared_day = |line: &str| {
+        let module = line.strip_prefix("pub mod d")?.strip_suffix(';')?;
+        (module.len() == 2).then(|| module.parse::<u8>().ok())?
+    };
+
+    if lines.contains(&declaration.as_str()) {
+        return None;
+    }
+
+    let idx = lines
+        .iter()
+        .rposition(|line| declared_day(line).is_some_and(|declared| declared < day.get()))
+        .map_or(lines.len(), |idx| idx + 1);
+
+    lines.insert(idx, &declaration);
+
+    Some(lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io};
+
+    use crate::{Answer, Day, Expected, Manifest, Part};
+
+    use super::{declare_module, module_source, scaffold, Scaffold};
+
+    #[test]
+    fn fill_in_the_template() {
+        let mut manifest = Manifest::default();
+        manifest.insert(
+            Day::new(12),
+            Part::One,
+            Expected {
+                answer: Answer::from(31),
+                checksum: 0,
+            },
+        );
+
+        let source = module_source(Day::new(12), Some("Hill Climbing Algorithm"), &manifest);
+
+        assert!(source.starts_with("//! Day 12: Hill Climbing Algorithm.\n"));
+        assert!(source.contains("registry.add(Day::new(12), Solver);"));
+        assert!(source.contains(
+            "#[ignore = \"the day is not solved yet\"]\n        \
+             sample: InputSource::Sample => { One: 31, Two: \"TODO\" },"
+        ));
+        assert!(!source.contains('@'));
+    }
+
+    #[test]
+    fn declare_the_module_next_to_the_other_days() {
+        let lib = "mod input;\n\
+                   #[cfg(feature = \"d01\")]\npub mod d01;\n\
+                   #[cfg(feature = \"d11\")]\npub mod d11;\n";
+
+        assert_eq!(
+            Some(
+                "mod input;\n\
+                 #[cfg(feature = \"d01\")]\npub mod d01;\n\
+                 pub mod d05;\n\
+                 #[cfg(feature = \"d11\")]\npub mod d11;\n"
+                    .to_string()
+            ),
+            declare_module(lib, Day::new(5))
+        );
+        assert_eq!(None, declare_module(lib, Day::new(11)));
+    }
+
+    #[test]
+    fn never_replace_an_existing_day() {
+        let dir = std::env::temp_dir().join(format!("aoc_scaffold_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("lib.rs"), "pub mod d11;\n").unwrap();
+        let sample = dir.join("res").join("day_12_sample.txt");
+        let manifest = Manifest::default();
+
+        assert_eq!(
+            Scaffold {
+                module: dir.join("d12.rs"),
+                sample: Some(sample.clone()),
+                answers: Vec::new(),
+            },
+            scaffold(Day::new(12), None, &dir, &sample, &manifest).unwrap()
+        );
+        assert!(fs::read_to_string(dir.join("d12.rs"))
+            .unwrap()
+            .starts_with("//! Day 12.\n"));
+        assert_eq!(
+            "pub mod d11;\npub mod d12;\n",
+            fs::read_to_string(dir.join("lib.rs")).unwrap()
+        );
+
+        fs::write(dir.join("d12.rs"), "// solved\n").unwrap();
+        let error = scaffold(Day::new(12), None, &dir, &sample, &manifest).unwrap_err();
+
+        assert_eq!(io::ErrorKind::AlreadyExists, error.kind());
+        assert_eq!(
+            "// solved\n",
+            fs::read_to_string(dir.join("d12.rs")).unwrap()
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
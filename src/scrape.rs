@@ -0,0 +1,418 @@
+use std::{fs, io, path::Path};
+
+use crate::{checksum, Answer, Day, Expected, Manifest, Part};
+
+/// The examples and sample answer of one part of a puzzle description.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PartDescription {
+    /// The contents of every `<pre><code>` block, in page order.
+    pub examples: Vec<String>,
+    /// The last emphasized `<code>` of the part, which is where the sample answer is given.
+    pub answer: Option<Answer>,
+}
+
+/// What could be read from a saved or downloaded puzzle page.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PuzzlePage {
+    /// One entry per `<article>`; part two only shows up once part one is solved.
+    pub parts: Vec<PartDescription>,
+}
+
+impl PuzzlePage {
+    /// Reads the puzzle descriptions out of a page's HTML.
+    #[must_use]
+    pub fn parse(html: &str) -> Self {
+        let mut articles = elements(html, "<article", "</article>").peekable();
+
+        let parts = if articles.peek().is_some() {
+            articles.map(PartDescription::parse).collect()
+        } else {
+            vec![PartDescription::parse(html)]
+        };
+
+        Self { parts }
+    }
+
+    /// The first example of part one, which is the sample input.
+    #[must_use]
+    pub fn sample(&self) -> Option<&str> {
+        self.parts.first()?.examples.first().map(String::as_str)
+    }
+
+    /// The sample answer of each part the page describes.
+    pub fn answers(&self) -> impl Iterator<Item = (Part, &Answer)> {
+        Part::ALL
+            .into_iter()
+            .zip(&self.parts)
+            .filter_map(|(part, description)| Some((part, description.answer.as_ref()?)))
+    }
+}
+
+impl PartDescription {
+    fn parse(html: &str) -> Self {
+        let examples = elements(html, "<pre><code>", "</code></pre>")
+            .map(text)
+            .collect();
+
+        let answer = elements(html, "<code><em>", "</em></code>")
+            .chain(elements(html, "<em><code>", "</code></em>"))
+            .max_by_key(|element| element.as_ptr())
+            .map(|element| {
+                let Ok(answer) = text(element).trim().parse();
+                answer
+            });
+
+        Self { examples, answer }
+    }
+}
+
+/// The contents between every `open` and the following `close`.
+fn elements<'a>(html: &'a str, open: &'a str, close: &'a str) -> impl Iterator<Item = &'a str> {
+    let mut rest = html;
+
+    std::iter::from_fn(move || {
+        let start = rest.find(open)?;
+        // `<article` may carry attributes, so its contents start where the tag closes.
+        let start = if open.ends_with('>') {
+            start + open.len()
+        } else {
+            start + rest[start..].find('>')? + 1
+        };
+        let end = start + rest[start..].find(close)?;
+
+        let element = &rest[start..end];
+        rest = &rest[end + close.len()..];
+
+        Some(element)
+    })
+}
+
+/// `html` without tags and with entities decoded.
+fn text(html: &str) -> String {
+    let mut stripped = String::new();
+    let mut in_tag = false;
+
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => stripped.push(c),
+            _ => {}
+        }
+    }
+
+    stripped
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&amp;", "&")
+}
+
+/// What happened to the sample file when saving a page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleFile {
+    Written,
+    Unchanged,
+    /// The file already holds a different sample and was kept. No answers were recorded.
+    Differs,
+    /// The page has no example.
+    Missing,
+}
+
+/// Writes the page's sample to `sample_path` unless it already holds one, and records the
+/// sample answers in `manifest`.
+///
+/// Returns what happened to the sample file and the parts whose answers were recorded.
+///
+/// # Errors
+///
+/// Returns an error if the sample file cannot be read or written.
+pub fn save_sample(
+    day: Day,
+    page: &PuzzlePage,
+    sample_path: &Path,
+    manifest: &mut Manifest,
+) -> io::Result<(SampleFile, Vec<Part>)> {
+    let Some(sample) = page.sample() else {
+        return Ok((SampleFile::Missing, Vec::new()));
+    };
+
+    let status = match fs::read_to_string(sample_path) {
+        Ok(existing) if existing == sample => SampleFile::Unchanged,
+        // `aoc2022 new` leaves an empty sample file to paste into.
+        Ok(existing) if !existing.trim().is_empty() => {
+            return Ok((SampleFile::Differs, Vec::new()))
+        }
+        Ok(_) => {
+            fs::write(sample_path, sample)?;
+            SampleFile::Written
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            if let Some(dir) = sample_path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(sample_path, sample)?;
+            SampleFile::Written
+        }
+        Err(e) => return Err(e),
+    };
+
+    let checksum = checksum(sample.as_bytes());
+    let mut recorded = Vec::new();
+
+    for (part, answer) in page.answers() {
+        manifest.insert(
+            day,
+            part,
+            Expected {
+                answer: answer.clone(),
+                checksum,
+            },
+        );
+        recorded.push(part);
+    }
+
+    Ok((status, recorded))
+}
+
+/// The start of the `sample_tests!` row that checks the day's sample file.
+const SAMPLE_ROW: &str = "sample: InputSource::Sample => {";
+
+/// Writes the sample answers in `manifest` into the `sample: InputSource::Sample` row of the
+/// `sample_tests!` block in the solver module at `module`, adding the row if there is none.
+///
+/// Only parts the row leaves out or expects `"TODO"` for are filled in, so answers written
+/// by hand are kept. Returns whether the module changed.
+///
+/// # Errors
+///
+/// Returns an error if the module cannot be read or written, or has no `sample_tests!` block.
+pub fn update_sample_row(day: Day, module: &Path, manifest: &Manifest) -> io::Result<bool> {
+    let source = fs::read_to_string(module)?;
+    let no_tests = || {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} has no sample_tests! block", module.display()),
+        )
+    };
+
+    let block = source.find("sample_tests! {").ok_or_else(no_tests)?;
+    let updated = if let Some(offset) = source[block..].find(SAMPLE_ROW) {
+        let start = block + offset;
+        let end = source[start..]
+            .find('\n')
+            .map_or(source.len(), |len| start + len);
+        let answers = sample_answers(day, manifest, Some(&source[start..end]));
+        format!(
+            "{}{SAMPLE_ROW} {answers} }},{}",
+            &source[..start],
+            &source[end..]
+        )
+    } else {
+        // The row goes right after the `Solver;` line that opens the block.
+        let start = source[block..].find(";\n").ok_or_else(no_tests)? + block + 2;
+        let answers = sample_answers(day, manifest, None);
+        format!(
+            "{}        {SAMPLE_ROW} {answers} }},\n{}",
+            &source[..start],
+            &source[start..]
+        )
+    };
+
+    if updated == source {
+        return Ok(false);
+    }
+    fs::write(module, updated)?;
+
+    Ok(true)
+}
+
+/// The `Part: expected` entries of a sample row for `day`.
+///
+/// Entries of the existing `row` are kept unless they expect `"TODO"`; the rest come from
+/// `manifest`. Without a row, parts the manifest has no answer for expect `"TODO"` until
+/// filled in.
+pub(crate) fn sample_answers(day: Day, manifest: &Manifest, row: Option<&str>) -> String {
+    Part::ALL
+        .iter()
+        .filter_map(|&part| {
+            let name = if part == Part::One { "One" } else { "Two" };
+            let written = row.and_then(|row| row_entry(row, name));
+            let expected = written
+                .filter(|&expected| expected != "\"TODO\"")
+                .map(str::to_owned)
+                .or_else(|| Some(answer_literal(&manifest.get(day, part)?.answer)))
+                .or_else(|| written.map(str::to_owned))
+                .or_else(|| row.is_none().then(|| "\"TODO\"".to_string()))?;
+            Some(format!("{name}: {expected}"))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// What `row` expects for the part called `name`, as written.
+fn row_entry<'a>(row: &'a str, name: &str) -> Option<&'a str> {
+    let entries = row.split_once('{')?.1.rsplit_once('}')?.0;
+    let entry = entries.split_once(&format!("{name}:"))?.1;
+    let entry = entry.split(", One:").next()?.split(", Two:").next()?;
+
+    Some(entry.trim().trim_end_matches(',').trim_end())
+}
+
+/// `answer` as a Rust expression that converts into it.
+fn answer_literal(answer: &Answer) -> String {
+    match answer {
+        // Integer literals are `i32` unless they say otherwise.
+        Answer::Integer(value) if i32::try_from(*value).is_ok() => value.to_string(),
+        Answer::Integer(value) if *value >= 0 => format!("{value}_u64"),
+        Answer::Integer(value) => format!("{value}_i64"),
+        answer => format!("{:?}", answer.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{checksum, Answer, Day, Expected, Manifest, Part};
+
+    use super::{save_sample, update_sample_row, PuzzlePage, SampleFile};
+
+    const PAGE: &str = r#"<html><body><main>
+<article class="day-desc"><h2>--- Day 1: Calorie Counting ---</h2>
+<p>For example, suppose the Elves finish writing their items' <code>Calories</code>:</p>
+<pre><code>1000
+2000
+
+&lt;3000&gt;
+</code></pre>
+<p>In the example above, this is <em>9000</em> (carried by the <code><em>first</em></code> Elf).</p>
+<p>Find the Elf carrying the most Calories. In the example, that is <code><em>6000</em></code>.</p>
+</article>
+<p>Your puzzle answer was <code>71300</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>In the example above, the top two Elves carry <em><code>9000</code></em> Calories.</p>
+</article>
+</main></body></html>"#;
+
+    #[test]
+    fn read_examples_and_answers() {
+        let page = PuzzlePage::parse(PAGE);
+
+        assert_eq!(Some("1000\n2000\n\n<3000>\n"), page.sample());
+        assert_eq!(
+            vec![
+                (Part::One, &Answer::from(6000)),
+                (Part::Two, &Answer::from(9000))
+            ],
+            page.answers().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn read_pages_without_articles() {
+        let page = PuzzlePage::parse("<pre><code>a &amp; b</code></pre><code><em>AB</em></code>");
+
+        assert_eq!(Some("a & b"), page.sample());
+        assert_eq!(Some(Answer::from("AB")), page.parts[0].answer);
+    }
+
+    #[test]
+    fn save_samples_without_overwriting() {
+        let path = std::env::temp_dir().join(format!("aoc_scrape_{}.txt", std::process::id()));
+        std::fs::write(&path, "").unwrap();
+        let page = PuzzlePage::parse(PAGE);
+        let mut manifest = Manifest::default();
+        let day = Day::new(1);
+
+        assert_eq!(
+            (SampleFile::Written, vec![Part::One, Part::Two]),
+            save_sample(day, &page, &path, &mut manifest).unwrap()
+        );
+        assert_eq!(
+            Some(&Expected {
+                answer: Answer::from(9000),
+                checksum: checksum(b"1000\n2000\n\n<3000>\n"),
+            }),
+            manifest.get(day, Part::Two)
+        );
+        assert_eq!(
+            SampleFile::Unchanged,
+            save_sample(day, &page, &path, &mut manifest).unwrap().0
+        );
+
+        std::fs::write(&path, "edited by hand\n").unwrap();
+        let mut untouched = Manifest::default();
+        assert_eq!(
+            (SampleFile::Differs, Vec::new()),
+            save_sample(day, &page, &path, &mut untouched).unwrap()
+        );
+        assert_eq!(None, untouched.get(day, Part::One));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn fill_in_the_sample_row() {
+        let path = std::env::temp_dir().join(format!("aoc_scrape_d12_{}.rs", std::process::id()));
+        let day = Day::new(12);
+        let mut manifest = Manifest::default();
+        for (part, answer) in [
+            (Part::One, Answer::from(31)),
+            (Part::Two, Answer::from(5_000_000_000_u64)),
+        ] {
+            manifest.insert(
+                day,
+                part,
+                Expected {
+                    answer,
+                    checksum: 0,
+                },
+            );
+        }
+        let module = |row: &str| {
+            format!("mod tests {{\n    sample_tests! {{\n        super::Solver;\n{row}    }}\n}}\n")
+        };
+
+        std::fs::write(&path, module("")).unwrap();
+        assert!(update_sample_row(day, &path, &manifest).unwrap());
+        assert_eq!(
+            module("        sample: InputSource::Sample => { One: 31, Two: 5000000000_u64 },\n"),
+            std::fs::read_to_string(&path).unwrap()
+        );
+
+        std::fs::write(
+            &path,
+            module("        sample: InputSource::Sample => { One: 30, Two: \"TODO\" },\n"),
+        )
+        .unwrap();
+        assert!(update_sample_row(day, &path, &manifest).unwrap());
+        assert_eq!(
+            module("        sample: InputSource::Sample => { One: 30, Two: 5000000000_u64 },\n"),
+            std::fs::read_to_string(&path).unwrap()
+        );
+        assert!(!update_sample_row(day, &path, &manifest).unwrap());
+
+        let row = "        sample: InputSource::Sample => { One: \"TODO\", Two: \"TODO\" },\n";
+        std::fs::write(&path, module(row)).unwrap();
+        manifest = Manifest::default();
+        manifest.insert(
+            day,
+            Part::One,
+            Expected {
+                answer: Answer::from("AB"),
+                checksum: 0,
+            },
+        );
+        assert!(update_sample_row(day, &path, &manifest).unwrap());
+        assert_eq!(
+            module("        sample: InputSource::Sample => { One: \"AB\", Two: \"TODO\" },\n"),
+            std::fs::read_to_string(&path).unwrap()
+        );
+
+        std::fs::write(&path, "fn main() {}\n").unwrap();
+        assert!(update_sample_row(day, &path, &manifest).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,550 @@
+use std::{
+    fmt,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{client::YEAR, input_dir, timestamp, Answer, Client, ClientError, Day, Part};
+
+/// Where guesses are recorded, inside the input directory, unless another file is given.
+pub const DEFAULT_GUESS_FILE: &str = "guesses.tsv";
+
+const HEADER: &str = "# day\tpart\ttimestamp\toutcome\tanswer";
+
+/// What the server said about a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint in which direction.
+    Wrong,
+    /// The answer was not checked because the last guess was too recent.
+    Wait(Duration),
+    /// The part was already solved, so the answer was not checked.
+    AlreadySolved,
+}
+
+impl Outcome {
+    /// Reads the outcome from the page the server answers a submission with.
+    #[must_use]
+    pub fn from_response(body: &str) -> Option<Self> {
+        if body.contains("That's the right answer") {
+            Some(Self::Correct)
+        } else if body.contains("That's not the right answer") {
+            Some(if body.contains("your answer is too high") {
+                Self::TooHigh
+            } else if body.contains("your answer is too low") {
+                Self::TooLow
+            } else {
+                Self::Wrong
+            })
+        } else if body.contains("You gave an answer too recently") {
+            Some(Self::Wait(parse_wait(body).unwrap_or_default()))
+        } else if body.contains("You don't seem to be solving the right level") {
+            Some(Self::AlreadySolved)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the answer was checked and found wrong.
+    #[must_use]
+    pub const fn is_wrong(self) -> bool {
+        matches!(self, Self::TooHigh | Self::TooLow | Self::Wrong)
+    }
+
+    fn to_field(self) -> String {
+        match self {
+            Self::Correct => "correct".to_string(),
+            Self::TooHigh => "too_high".to_string(),
+            Self::TooLow => "too_low".to_string(),
+            Self::Wrong => "wrong".to_string(),
+            Self::Wait(wait) => format!("wait_{}", wait.as_secs()),
+            Self::AlreadySolved => "already_solved".to_string(),
+        }
+    }
+
+    fn from_field(field: &str) -> Option<Self> {
+        Some(match field {
+            "correct" => Self::Correct,
+            "too_high" => Self::TooHigh,
+            "too_low" => Self::TooLow,
+            "wrong" => Self::Wrong,
+            "already_solved" => Self::AlreadySolved,
+            _ => Self::Wait(Duration::from_secs(
+                field.strip_prefix("wait_")?.parse().ok()?,
+            )),
+        })
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Correct => f.pad("correct"),
+            Self::TooHigh => f.pad("too high"),
+            Self::TooLow => f.pad("too low"),
+            Self::Wrong => f.pad("wrong"),
+            Self::Wait(wait) => f.pad(&format!("wait {}s", wait.as_secs())),
+            Self::AlreadySolved => f.pad("already solved"),
+        }
+    }
+}
+
+/// Reads "You have 1m 30s left to wait."
+fn parse_wait(body: &str) -> Option<Duration> {
+    let start = body.find("You have ")? + "You have ".len();
+    let end = start + body[start..].find(" left to wait")?;
+
+    let mut seconds = 0;
+    for amount in body[start..end].split_whitespace() {
+        let (value, unit) = amount.split_at(amount.find(|c: char| !c.is_ascii_digit())?);
+        let value: u64 = value.parse().ok()?;
+        seconds += match unit {
+            "h" => value * 3600,
+            "m" => value * 60,
+            "s" => value,
+            _ => return None,
+        };
+    }
+
+    Some(Duration::from_secs(seconds))
+}
+
+/// One submitted answer and what the server said about it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Guess {
+    pub day: Day,
+    pub part: Part,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub outcome: Outcome,
+    pub answer: Answer,
+}
+
+impl Guess {
+    fn to_line(&self) -> String {
+        // Multiline answers are kept on one line, as in the answers manifest.
+        let answer = self.answer.to_string().replace('\n', "\\n");
+
+        format!(
+            "{}\t{}\t{}\t{}\t{answer}",
+            self.day,
+            self.part,
+            self.timestamp,
+            self.outcome.to_field()
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(5, '\t');
+
+        let day = Day::try_new(fields.next()?.parse().ok()?)?;
+        let part = match fields.next()? {
+            "1" => Part::One,
+            "2" => Part::Two,
+            _ => return None,
+        };
+        let timestamp = fields.next()?.parse().ok()?;
+        let outcome = Outcome::from_field(fields.next()?)?;
+        let Ok(answer) = fields.next()?.replace("\\n", "\n").parse();
+
+        Some(Self {
+            day,
+            part,
+            timestamp,
+            outcome,
+            answer,
+        })
+    }
+}
+
+/// Why an answer was not submitted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Refusal {
+    /// The part was already solved with this answer.
+    Solved(Answer),
+    /// The same answer was submitted before and was wrong.
+    KnownWrong(Outcome),
+    /// A smaller or equal answer was already too high.
+    AboveBound(i128),
+    /// A larger or equal answer was already too low.
+    BelowBound(i128),
+    /// The answer is a drawing that has to be read as letters first.
+    Multiline,
+}
+
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Solved(answer) => write!(f, "already solved with `{answer}`"),
+            Self::KnownWrong(outcome) => {
+                write!(f, "this answer was already submitted and was {outcome}")
+            }
+            Self::AboveBound(bound) => write!(f, "`{bound}` was already too high"),
+            Self::BelowBound(bound) => write!(f, "`{bound}` was already too low"),
+            Self::Multiline => write!(f, "multiline answers have to be read and typed in"),
+        }
+    }
+}
+
+/// Every guess recorded in a guess file, oldest first.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GuessHistory {
+    pub guesses: Vec<Guess>,
+}
+
+impl GuessHistory {
+    /// [`DEFAULT_GUESS_FILE`] in the input directory.
+    #[must_use]
+    pub fn default_path() -> PathBuf {
+        input_dir().join(DEFAULT_GUESS_FILE)
+    }
+
+    /// Reads the guesses stored at `path`. A missing file is an empty history.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or a line is malformed.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e),
+        };
+
+        let guesses = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(idx, line)| {
+                Guess::from_line(line).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("{}:{}: malformed guess", path.display(), idx + 1),
+                    )
+                })
+            })
+            .collect::<io::Result<_>>()?;
+
+        Ok(Self { guesses })
+    }
+
+    /// Appends `guess` to the file at `path`, creating it if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn append(path: &Path, guess: &Guess) -> io::Result<()> {
+        let is_new = !path.exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        if is_new {
+            writeln!(file, "{HEADER}")?;
+        }
+        writeln!(file, "{}", guess.to_line())
+    }
+
+    /// Checks `answer` against every earlier guess for `day` and `part`.
+    ///
+    /// # Errors
+    ///
+    /// Returns why the answer is already known to be wrong, or why it cannot be submitted.
+    pub fn check(&self, day: Day, part: Part, answer: &Answer) -> Result<(), Refusal> {
+        if answer.is_multiline() {
+            return Err(Refusal::Multiline);
+        }
+
+        let guesses = self
+            .guesses
+            .iter()
+            .filter(|guess| guess.day == day && guess.part == part);
+
+        for guess in guesses.clone() {
+            if guess.outcome == Outcome::Correct {
+                return Err(Refusal::Solved(guess.answer.clone()));
+            }
+            // Compared as text, since reading the history can turn text into a number.
+            if guess.outcome.is_wrong() && guess.answer.to_string() == answer.to_string() {
+                return Err(Refusal::KnownWrong(guess.outcome));
+            }
+        }
+
+        let Answer::Integer(value) = *answer else {
+            return Ok(());
+        };
+        let bound = |outcome| {
+            guesses.clone().filter_map(move |guess| match guess.answer {
+                Answer::Integer(bound) if guess.outcome == outcome => Some(bound),
+                _ => None,
+            })
+        };
+
+        if let Some(high) = bound(Outcome::TooHigh).min().filter(|&high| value >= high) {
+            return Err(Refusal::AboveBound(high));
+        }
+        if let Some(low) = bound(Outcome::TooLow).max().filter(|&low| value <= low) {
+            return Err(Refusal::BelowBound(low));
+        }
+
+        Ok(())
+    }
+}
+
+/// A submission failed before the server could judge the answer.
+#[derive(Debug)]
+pub enum SubmitError {
+    Refused(Refusal),
+    Client(ClientError),
+    /// The server's page has none of the messages an outcome is read from.
+    UnknownResponse(String),
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Refused(refusal) => write!(f, "not submitted: {refusal}"),
+            Self::Client(e) => write!(f, "{e}"),
+            Self::UnknownResponse(text) => write!(f, "unexpected response: {text}"),
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Client(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ClientError> for SubmitError {
+    fn from(e: ClientError) -> Self {
+        Self::Client(e)
+    }
+}
+
+/// Submits `answer` for `day` and `part` unless `history` already rules it out.
+///
+/// The returned guess is not added to `history`; save it with [`GuessHistory::append`].
+///
+/// # Errors
+///
+/// Returns an error if the answer is refused, the request fails or the response cannot be
+/// understood.
+pub fn submit(
+    client: &mut Client,
+    history: &GuessHistory,
+    day: Day,
+    part: Part,
+    answer: &Answer,
+) -> Result<Guess, SubmitError> {
+    history
+        .check(day, part, answer)
+        .map_err(SubmitError::Refused)?;
+
+    let level = part.to_string();
+    let value = answer.to_string();
+    let response = client.post_form(
+        &format!("/{YEAR}/day/{day}/answer"),
+        &[("level", &level), ("answer", &value)],
+    )?;
+
+    if response.status != 200 {
+        return Err(ClientError::Status {
+            status: response.status,
+            body: response.body,
+        }
+        .into());
+    }
+
+    let outcome = Outcome::from_response(&response.body)
+        .ok_or_else(|| SubmitError::UnknownResponse(article_text(&response.body)))?;
+
+    Ok(Guess {
+        day,
+        part,
+        timestamp: timestamp(),
+        outcome,
+        answer: answer.clone(),
+    })
+}
+
+/// The text of the page's `<article>`, or of the whole page, without tags.
+fn article_text(body: &str) -> String {
+    let article = body
+        .find("<article")
+        .and_then(|start| Some(&body[start..start + body[start..].find("</article>")?]))
+        .unwrap_or(body);
+
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        client::tests::{ok, serve},
+        Answer, Client, Day, Part,
+    };
+
+    use super::{submit, Guess, GuessHistory, Outcome, Refusal, SubmitError};
+
+    fn guess(part: Part, answer: impl Into<Answer>, outcome: Outcome) -> Guess {
+        Guess {
+            day: Day::new(11),
+            part,
+            timestamp: 1_670_000_000,
+            outcome,
+            answer: answer.into(),
+        }
+    }
+
+    #[test]
+    fn read_outcomes() {
+        let cases = [
+            (
+                "<p>That's the right answer! You are one gold star closer.</p>",
+                Outcome::Correct,
+            ),
+            (
+                "<p>That's not the right answer; your answer is too high.</p>",
+                Outcome::TooHigh,
+            ),
+            (
+                "<p>That's not the right answer; your answer is too low.</p>",
+                Outcome::TooLow,
+            ),
+            (
+                "<p>That's not the right answer. If you're stuck...</p>",
+                Outcome::Wrong,
+            ),
+            (
+                "<p>You gave an answer too recently. You have 1m 30s left to wait.</p>",
+                Outcome::Wait(Duration::from_secs(90)),
+            ),
+            (
+                "<p>You don't seem to be solving the right level. Did you already complete it?</p>",
+                Outcome::AlreadySolved,
+            ),
+        ];
+
+        for (body, outcome) in cases {
+            assert_eq!(Some(outcome), Outcome::from_response(body), "{body}");
+            assert_eq!(Some(outcome), Outcome::from_field(&outcome.to_field()));
+        }
+        assert_eq!(None, Outcome::from_response("<p>Welcome!</p>"));
+    }
+
+    #[test]
+    fn refuse_known_wrong_and_out_of_bounds_answers() {
+        let history = GuessHistory {
+            guesses: vec![
+                guess(Part::Two, 100, Outcome::TooLow),
+                guess(Part::Two, 500, Outcome::TooHigh),
+                guess(Part::Two, 300, Outcome::Wrong),
+                guess(Part::Two, 400, Outcome::TooHigh),
+                guess(Part::One, "ABC", Outcome::Correct),
+            ],
+        };
+        let check = |answer: Answer| history.check(Day::new(11), Part::Two, &answer);
+
+        assert_eq!(Ok(()), check(Answer::from(200)));
+        assert_eq!(
+            Err(Refusal::KnownWrong(Outcome::Wrong)),
+            check(Answer::from(300))
+        );
+        assert_eq!(Err(Refusal::AboveBound(400)), check(Answer::from(450)));
+        assert_eq!(Err(Refusal::BelowBound(100)), check(Answer::from(50)));
+        assert_eq!(Err(Refusal::Multiline), check(Answer::from("#.\n.#")));
+        assert_eq!(
+            Err(Refusal::Solved(Answer::from("ABC"))),
+            history.check(Day::new(11), Part::One, &Answer::from("XYZ"))
+        );
+        assert_eq!(
+            Ok(()),
+            history.check(Day::new(10), Part::Two, &Answer::from(450))
+        );
+    }
+
+    #[test]
+    fn save_and_load_guesses() {
+        let path = std::env::temp_dir().join(format!("aoc_guesses_{}.tsv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let guesses = vec![
+            guess(Part::One, 10605, Outcome::TooHigh),
+            guess(
+                Part::Two,
+                "line\nbreak",
+                Outcome::Wait(Duration::from_secs(30)),
+            ),
+        ];
+        for guess in &guesses {
+            GuessHistory::append(&path, guess).unwrap();
+        }
+
+        assert_eq!(GuessHistory { guesses }, GuessHistory::load(&path).unwrap());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn submit_to_a_stand_in_server() {
+        let (base_url, server) = serve(vec![
+            ok("<main><article><p>That's not the right answer; your answer is too low.</p></article></main>"),
+            ok("<main><article><p>Please <a href=\"/\">log in</a>.</p></article></main>"),
+        ]);
+        let mut client = Client::new(&base_url, "secret")
+            .unwrap()
+            .with_interval(Duration::ZERO);
+        let mut history = GuessHistory::default();
+
+        let guess = submit(
+            &mut client,
+            &history,
+            Day::new(11),
+            Part::Two,
+            &Answer::from(42),
+        )
+        .unwrap();
+        assert_eq!(Outcome::TooLow, guess.outcome);
+        history.guesses.push(guess);
+
+        assert!(matches!(
+            submit(
+                &mut client,
+                &history,
+                Day::new(11),
+                Part::Two,
+                &Answer::from(41)
+            ),
+            Err(SubmitError::Refused(Refusal::BelowBound(42)))
+        ));
+        assert!(matches!(
+            submit(&mut client, &history, Day::new(11), Part::Two, &Answer::from(43)),
+            Err(SubmitError::UnknownResponse(text)) if text == "Please log in."
+        ));
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /2022/day/11/answer HTTP/1.0\r\n"));
+        assert!(requests[0].ends_with("\r\n\r\nlevel=2&answer=42"));
+    }
+}
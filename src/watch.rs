@@ -0,0 +1,107 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::{Day, InputSource};
+
+/// How often `aoc2022 watch` looks at the input files by default.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// What a file looked like when it was last polled. `None` means it did not exist.
+type Stamp = Option<(SystemTime, u64)>;
+
+/// Tracks the input file of each watched day and reports the days whose file changed.
+///
+/// Polls modification times and sizes, so it needs nothing beyond `std`. Solver sources are
+/// compiled in and need a rebuild, which this cannot do.
+#[derive(Debug)]
+pub struct InputWatcher {
+    files: BTreeMap<Day, (PathBuf, Stamp)>,
+}
+
+impl InputWatcher {
+    /// Starts watching the file `source` reads for each of `days`, as it is now.
+    ///
+    /// Days whose source is not a file (standard input, in-memory text) are never reported.
+    #[must_use]
+    pub fn new(days: &[Day], source: &InputSource) -> Self {
+        let files = days
+            .iter()
+            .filter_map(|&day| {
+                let path = source.path(day)?;
+                let stamp = stamp(&path);
+                Some((day, (path, stamp)))
+            })
+            .collect();
+
+        Self { files }
+    }
+
+    /// The days whose input was written, created or removed since the last call.
+    pub fn changed(&mut self) -> Vec<Day> {
+        self.files
+            .iter_mut()
+            .filter_map(|(&day, (path, last))| {
+                let now = stamp(path);
+                (now != *last).then(|| {
+                    *last = now;
+                    day
+                })
+            })
+            .collect()
+    }
+
+    /// The file watched for `day`, if any.
+    #[must_use]
+    pub fn path(&self, day: Day) -> Option<&Path> {
+        self.files.get(&day).map(|(path, _)| path.as_path())
+    }
+}
+
+fn stamp(path: &Path) -> Stamp {
+    let metadata = fs::metadata(path).ok()?;
+
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::{Day, InputSource};
+
+    use super::InputWatcher;
+
+    #[test]
+    fn report_only_the_days_that_changed() {
+        let dir = std::env::temp_dir().join(format!("aoc_watch_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let (one, two) = (dir.join("one.txt"), dir.join("two.txt"));
+        fs::write(&one, "1\n").unwrap();
+        fs::write(&two, "2\n").unwrap();
+
+        let mut watcher = InputWatcher::new(&[Day::new(1)], &InputSource::Path(one.clone()));
+        watcher
+            .files
+            .extend(InputWatcher::new(&[Day::new(2)], &InputSource::Path(two.clone())).files);
+
+        assert!(watcher.changed().is_empty());
+
+        fs::write(&two, "2\n3\n").unwrap();
+        assert_eq!(vec![Day::new(2)], watcher.changed());
+        assert!(watcher.changed().is_empty());
+
+        fs::remove_file(&one).unwrap();
+        assert_eq!(vec![Day::new(1)], watcher.changed());
+        assert_eq!(Some(one.as_path()), watcher.path(Day::new(1)));
+        assert!(InputWatcher::new(&[Day::new(1)], &InputSource::Stdin)
+            .path(Day::new(1))
+            .is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,76 @@
+//! Day @DAY@@TITLE@.
+
+use crate::{
+    error::Line, get_day_input, read_lines, registry::Registry, AOCSolver, Answer, AocError, Day,
+    InputSource,
+};
+
+/// Solves both parts of day @DAY@ through [`AOCSolver`].
+pub struct Solver;
+
+impl AOCSolver for Solver {
+    type Input = Vec<String>;
+    type Params = ();
+
+    fn parse(source: &InputSource) -> Result<Self::Input, AocError> {
+        parse_input(source)
+    }
+
+    fn part_one(input: &Self::Input, _: &()) -> Result<Answer, AocError> {
+        solve_part_one(input).map(Answer::from)
+    }
+
+    fn part_two(input: &Self::Input, _: &()) -> Result<Answer, AocError> {
+        solve_part_two(input).map(Answer::from)
+    }
+}
+
+pub(crate) fn register(registry: &mut Registry) {
+    registry.add(Day::new(@DAY@), Solver);
+}
+
+/// Reads the puzzle input, one line at a time.
+///
+/// # Errors
+///
+/// Returns an error if the input cannot be read or a line is malformed.
+pub fn parse_input(source: &InputSource) -> Result<Vec<String>, AocError> {
+    let day = Day::new(@DAY@);
+    let file = get_day_input(day, source)?;
+
+    let mut lines = Vec::new();
+
+    for (idx, text) in read_lines(day, file).enumerate() {
+        let text = text?;
+        let line = Line::new(day, idx, &text);
+
+        lines.push(line.text().to_owned());
+    }
+
+    Ok(lines)
+}
+
+/// # Errors
+///
+/// Returns an error until part one is solved.
+pub fn solve_part_one(_lines: &[String]) -> Result<u64, AocError> {
+    Err(AocError::Unsolved(Day::new(@DAY@)))
+}
+
+/// # Errors
+///
+/// Returns an error until part two is solved.
+pub fn solve_part_two(_lines: &[String]) -> Result<u64, AocError> {
+    Err(AocError::Unsolved(Day::new(@DAY@)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::InputSource;
+
+    sample_tests! {
+        super::Solver;
+        #[ignore = "the day is not solved yet"]
+        sample: InputSource::Sample => { @SAMPLE_ANSWERS@ },
+    }
+}